@@ -0,0 +1,305 @@
+use chrono::{DateTime, Datelike, Days, Months, NaiveDate};
+use dioxus::prelude::*;
+
+use crate::{
+    form::MIN_EXPIRATION_TTL,
+    history,
+    ics::IcsExport,
+    store::use_links,
+    time,
+    undo::{self, Undo},
+    utils::{self, Link, UpdateRequestBody},
+    Route,
+};
+
+const MONTH_FORMAT: &str = "%Y-%m";
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// The first day of a `YYYY-MM` month.
+pub fn parse_month(month: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d").ok()
+}
+
+pub fn local_date(timestamp: i64, offset: i32) -> Option<NaiveDate> {
+    DateTime::from_timestamp(timestamp + i64::from(offset), 0).map(|d| d.date_naive())
+}
+
+/// The days shown for the month starting at `first`, in whole weeks from
+/// Monday to Sunday.
+pub fn month_grid(first: NaiveDate) -> Vec<NaiveDate> {
+    let start = first - Days::new(u64::from(first.weekday().num_days_from_monday()));
+    let last = first + Months::new(1) - Days::new(1);
+    let end = last + Days::new(u64::from(6 - last.weekday().num_days_from_monday()));
+    start.iter_days().take_while(|d| *d <= end).collect()
+}
+
+/// `timestamp` moved to `date`, keeping its wall clock time.
+pub fn move_to_date(timestamp: i64, date: NaiveDate, offset: impl Fn(i64) -> i32) -> i64 {
+    let time = DateTime::from_timestamp(timestamp + i64::from(offset(timestamp)), 0)
+        .map(|d| d.time())
+        .unwrap_or_default();
+    time::to_timestamp(date.and_time(time), offset)
+}
+
+/// The request that moves a link to `expiration`. It keeps the destination, so
+/// the domain policy does not apply, see `Policy::check_change`.
+pub fn reschedule_body(
+    link: &Link,
+    expiration: i64,
+    now: i64,
+) -> Result<UpdateRequestBody, Box<str>> {
+    let url = link
+        .url
+        .clone()
+        .ok_or("The link has no destination to keep")?;
+    if expiration < now + i64::from(MIN_EXPIRATION_TTL) {
+        return Err("The new expiration must be in the future".into());
+    }
+    Ok(UpdateRequestBody {
+        short: link.short.key.clone(),
+        url,
+        expiration: Some(expiration),
+        expiration_ttl: None,
+    })
+}
+
+fn month_route(month: NaiveDate) -> Route {
+    Route::Calendar {
+        month: month.format(MONTH_FORMAT).to_string(),
+    }
+}
+
+#[component]
+pub fn Calendar(month: String) -> Element {
+    let links = use_links();
+    let dragged = use_signal(|| None::<Link>);
+    let now = utils::now();
+    let zone = time::browser_time_zone();
+    let today = local_date(now, time::offset(&zone, now)).unwrap_or_default();
+    let first = parse_month(&month).unwrap_or_else(|| today.with_day(1).unwrap_or(today));
+    let links = match &*links.read() {
+        Some(Ok(links)) => links.clone(),
+        Some(Err(e)) => return Err(e.clone().into()),
+        None => {
+            return rsx! {
+                div { class: "mb-2 text-2xl", "Loading..." }
+            }
+        }
+    };
+    let mut dated: Vec<(NaiveDate, Link)> = links
+        .iter()
+        .filter_map(|l| {
+            let expiration = l.expiration?;
+            Some((
+                local_date(expiration, time::offset(&zone, expiration))?,
+                l.clone(),
+            ))
+        })
+        .collect();
+    dated.sort_by_key(|(_, l)| l.expiration);
+    let on = |day: NaiveDate| -> Vec<Link> {
+        dated
+            .iter()
+            .filter(|(d, _)| *d == day)
+            .map(|(_, l)| l.clone())
+            .collect()
+    };
+    let grid = month_grid(first);
+    let month_days: Vec<NaiveDate> = grid
+        .iter()
+        .copied()
+        .filter(|d| d.month() == first.month())
+        .collect();
+    rsx! {
+        div {
+            div { class: "flex justify-center items-center gap-4",
+                Link { class: "underline", to: month_route(first - Months::new(1)), "←" }
+                h1 { class: "text-3xl", "{first.format(\"%B %Y\")}" }
+                Link { class: "underline", to: month_route(first + Months::new(1)), "→" }
+            }
+            p { class: "text-sm text-gray-500 text-center",
+                "Drag a link to another day to change its expiration, the time of day is kept."
+            }
+            div { class: "grid grid-cols-7 border-t border-l border-gray-300 mt-2",
+                for name in WEEKDAYS {
+                    div { class: "border-r border-b border-gray-300 text-center text-sm", "{name}" }
+                }
+                for day in grid {
+                    DayCell {
+                        key: "{day}",
+                        day,
+                        today,
+                        outside: day.month() != first.month(),
+                        links: on(day),
+                        dragged,
+                    }
+                }
+            }
+            IcsExport { links: links.to_vec() }
+            h2 { class: "text-xl mt-5", "Timeline" }
+            div { class: "flex overflow-x-auto border-t border-l border-gray-300",
+                for day in month_days {
+                    div { class: "w-20 shrink-0",
+                        DayCell {
+                            key: "timeline-{day}",
+                            day,
+                            today,
+                            outside: false,
+                            links: on(day),
+                            dragged,
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn DayCell(
+    day: NaiveDate,
+    today: NaiveDate,
+    outside: bool,
+    links: Vec<Link>,
+    dragged: Signal<Option<Link>>,
+) -> Element {
+    let mut store = use_links();
+    let toast = undo::use_undo();
+    let mut dragged = dragged;
+    let mut over = use_signal(|| false);
+    let background = if over() {
+        "bg-yellow-100"
+    } else if day == today {
+        "bg-gray-100"
+    } else {
+        ""
+    };
+    let number = if outside { "text-gray-400" } else { "" };
+    rsx! {
+        div {
+            class: "border-r border-b border-gray-300 min-h-20 p-1 {background}",
+            ondragover: move |e| {
+                e.prevent_default();
+                over.set(true);
+            },
+            ondragleave: move |_| over.set(false),
+            ondrop: move |e| {
+                e.prevent_default();
+                over.set(false);
+                async move {
+                    let Some(link) = dragged.take() else {
+                        return;
+                    };
+                    let Some(expiration) = link.expiration else {
+                        return;
+                    };
+                    let zone = time::browser_time_zone();
+                    let new = move_to_date(expiration, day, |t| time::offset(&zone, t));
+                    if new == expiration {
+                        return;
+                    }
+                    let body = match reschedule_body(&link, new, utils::now()) {
+                        Ok(body) => body,
+                        Err(e) => {
+                            utils::alert(&e);
+                            return;
+                        }
+                    };
+                    let offset = time::offset(&zone, new);
+                    if !utils::confirm(
+                        &format!(
+                            "Change the expiration of {} to {}?",
+                            link.short.key,
+                            time::format_local(new, offset),
+                        ),
+                    ) {
+                        return;
+                    }
+                    let revert = Undo::revert(link.clone(), &body, utils::now());
+                    match history::update_link(&link, body).await {
+                        Ok(()) => {
+                            store.restart();
+                            undo::offer(toast, format!("Rescheduled {}", link.short.key), vec![revert]);
+                        }
+                        Err(e) => utils::alert(&format!("Failed to update the link.\n\nError: {}", e)),
+                    }
+                }
+            },
+            p { class: "text-sm {number}", "{day.day()}" }
+            for link in links {
+                div {
+                    class: "text-xs truncate cursor-move",
+                    draggable: link.url.is_some(),
+                    title: "{link.short.key}: {link.url.as_deref().unwrap_or_default()}",
+                    ondragstart: {
+                        let link = link.clone();
+                        move |_| dragged.set(Some(link.clone()))
+                    },
+                    ondragend: move |_| dragged.set(None),
+                    Link {
+                        class: if link.expiration.is_some_and(|e| e <= utils::now()) { "text-gray-400 underline" } else { "underline" },
+                        to: Route::LinkItem {
+                            link: link.short.key.to_string(),
+                        },
+                        "{link.short.key}"
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn months() {
+        assert_eq!(parse_month("2026-10"), Some(date(2026, 10, 1)));
+        assert_eq!(parse_month("2026-13"), None);
+        assert_eq!(parse_month(""), None);
+    }
+
+    #[test]
+    fn grid() {
+        // October 2026 starts on a Thursday and ends on a Saturday.
+        let grid = month_grid(date(2026, 10, 1));
+        assert_eq!(grid.len(), 35);
+        assert_eq!(grid[0], date(2026, 9, 28));
+        assert_eq!(grid[34], date(2026, 11, 1));
+        // February 2027 starts on a Monday and ends on a Sunday.
+        assert_eq!(month_grid(date(2027, 2, 1)).len(), 28);
+    }
+
+    #[test]
+    fn moving() {
+        // 2026-10-19 12:30 UTC.
+        let expiration = 1_792_413_000;
+        assert_eq!(
+            move_to_date(expiration, date(2026, 10, 21), |_| 0),
+            expiration + 2 * 86400
+        );
+        // 13:30 at +01:00 stays 13:30 local.
+        assert_eq!(
+            move_to_date(expiration, date(2026, 10, 18), |_| 3600),
+            expiration - 86400
+        );
+        assert_eq!(local_date(expiration, 12 * 3600), Some(date(2026, 10, 20)));
+    }
+
+    #[test]
+    fn body() {
+        let now = 1_792_413_000;
+        let link = utils::test_link("a", Some("https://a.b"), Some(now + 10));
+        let body = reschedule_body(&link, now + 86400, now).unwrap();
+        assert_eq!(body.expiration, Some(now + 86400));
+        assert_eq!(body.url.as_ref(), "https://a.b");
+        assert!(reschedule_body(&link, now - 10, now).is_err());
+        let broken = utils::test_link("a", None, Some(now + 10));
+        assert!(reschedule_body(&broken, now + 86400, now).is_err());
+    }
+}
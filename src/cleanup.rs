@@ -0,0 +1,251 @@
+use std::fmt;
+
+use dioxus::prelude::*;
+use serde::Serialize;
+
+use crate::{
+    history::{self, Removal},
+    store::use_links,
+    time::Expiration,
+    utils::{self, Link},
+    Route,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Reason {
+    Expired(i64),
+    MissingUrl,
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Reason::Expired(_) => write!(f, "The expiration is in the past"),
+            Reason::MissingUrl => write!(f, "The destination URL is missing"),
+        }
+    }
+}
+
+pub fn reasons(link: &Link, now: i64) -> Vec<Reason> {
+    let mut reasons = Vec::new();
+    if let Some(expiration) = link.expiration.filter(|e| *e <= now) {
+        reasons.push(Reason::Expired(expiration));
+    }
+    if link.url.is_none() {
+        reasons.push(Reason::MissingUrl);
+    }
+    reasons
+}
+
+/// One removed link in the exported record.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct Removed {
+    pub key: Box<str>,
+    pub short_url: Box<str>,
+    pub url: Option<Box<str>>,
+    pub expiration: Option<i64>,
+    pub reasons: Vec<String>,
+    pub removed_at: i64,
+}
+
+impl Removed {
+    pub fn new(link: &Link, reasons: &[Reason], removed_at: i64) -> Self {
+        Self {
+            key: link.short.key.clone(),
+            short_url: link.short.full.clone(),
+            url: link.url.clone(),
+            expiration: link.expiration,
+            reasons: reasons.iter().map(Reason::to_string).collect(),
+            removed_at,
+        }
+    }
+}
+
+pub fn record(removed: &[Removed]) -> String {
+    serde_json::to_string_pretty(removed).unwrap_or_default()
+}
+
+#[component]
+pub fn Cleanup() -> Element {
+    let mut links = use_links();
+    let mut deselected = use_signal(Vec::<Box<str>>::new);
+    let mut running = use_signal(|| false);
+    let mut removed = use_signal(Vec::<Removed>::new);
+    let mut failed = use_signal(Vec::<String>::new);
+    let now = utils::now();
+    let flagged: Vec<(Link, Vec<Reason>)> = match &*links.read() {
+        Some(Ok(links)) => links
+            .iter()
+            .map(|l| (l.clone(), reasons(l, now)))
+            .filter(|(_, reasons)| !reasons.is_empty())
+            .collect(),
+        Some(Err(e)) => return Err(e.clone().into()),
+        None => {
+            return rsx! {
+                div { class: "mb-2 text-2xl", "Loading..." }
+            }
+        }
+    };
+    let selected: Vec<(Link, Vec<Reason>)> = flagged
+        .iter()
+        .filter(|(l, _)| !deselected.read().contains(&l.short.key))
+        .cloned()
+        .collect();
+    let count = selected.len();
+    rsx! {
+        div {
+            div { class: "flex flex-col",
+                h1 { class: "text-3xl mx-auto", "Clean up" }
+            }
+            div { class: "flex flex-col w-9/12 sm:w-2/3 mx-auto mt-5",
+                p { class: "text-sm text-gray-500",
+                    "Links that have already expired or have no destination. They cannot be restored once deleted, download the record afterwards to keep a copy."
+                }
+                if flagged.is_empty() {
+                    p { class: "text-gray-500 mt-2", "Nothing to clean up." }
+                }
+                for (link , reasons) in flagged {
+                    label { class: "flex gap-2 items-start border-b border-gray-300 p-1",
+                        cursor: "pointer",
+                        input {
+                            r#type: "checkbox",
+                            checked: !deselected.read().contains(&link.short.key),
+                            onchange: {
+                                let key = link.short.key.clone();
+                                move |e: FormEvent| {
+                                    if e.checked() {
+                                        deselected.write().retain(|k| *k != key);
+                                    } else {
+                                        deselected.write().push(key.clone());
+                                    }
+                                }
+                            },
+                        }
+                        div { class: "flex-1 break-all",
+                            Link {
+                                class: "underline",
+                                to: Route::LinkItem {
+                                    link: link.short.key.to_string(),
+                                },
+                                "{link.short.key}"
+                            }
+                            if let Some(url) = &link.url {
+                                p { class: "text-sm", "{url}" }
+                            }
+                            for reason in reasons {
+                                p { class: "text-sm text-red-500",
+                                    "{reason}"
+                                    if let Reason::Expired(timestamp) = reason {
+                                        ": "
+                                        Expiration { timestamp }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                div { class: "mt-2 mx-auto",
+                    button {
+                        class: "border border-gray-300 hover:bg-gray-200 px-2 text-2xl",
+                        cursor: "pointer",
+                        disabled: running() || count == 0,
+                        onclick: move |_| {
+                            let selected = selected.clone();
+                            async move {
+                                if !utils::confirm(&format!("Delete {} links? This cannot be undone.", selected.len())) {
+                                    return;
+                                }
+                                running.set(true);
+                                removed.set(Vec::new());
+                                failed.set(Vec::new());
+                                for (link, reasons) in selected {
+                                    match history::delete_link(&link, Removal::Purged).await {
+                                        Ok(()) => removed.write().push(Removed::new(&link, &reasons, utils::now())),
+                                        Err(e) => failed.write().push(format!("{}: {}", link.short.key, e)),
+                                    }
+                                }
+                                running.set(false);
+                                deselected.set(Vec::new());
+                                links.restart();
+                            }
+                        },
+                        if running() {
+                            "Deleting..."
+                        } else {
+                            "Delete {count} links"
+                        }
+                    }
+                }
+                if !removed.read().is_empty() {
+                    div { class: "flex flex-wrap gap-1 items-center mt-2",
+                        span { "Deleted {removed.read().len()} links." }
+                        button {
+                            r#type: "button",
+                            class: "border border-gray-300 hover:bg-gray-200 px-2",
+                            cursor: "pointer",
+                            onclick: move |_| {
+                                utils::export(
+                                    &format!("linkrusk-cleanup-{}.json", now),
+                                    "application/json",
+                                    &record(&removed.read()),
+                                )
+                            },
+                            "Download record (.json)"
+                        }
+                    }
+                }
+                for failure in failed.read().iter() {
+                    p { class: "text-red-500 break-all", "{failure}" }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{policy::Policy, undo};
+
+    const NOW: i64 = 1_792_413_000;
+
+    #[test]
+    fn flagging() {
+        let reasons_for = |url, expiration| reasons(&utils::test_link("a", url, expiration), NOW);
+        assert!(reasons_for(Some("https://a.b"), None).is_empty());
+        assert!(reasons_for(Some("https://a.b"), Some(NOW + 1)).is_empty());
+        assert_eq!(
+            reasons_for(Some("https://a.b"), Some(NOW)),
+            [Reason::Expired(NOW)]
+        );
+        assert_eq!(
+            reasons_for(None, Some(NOW - 1)),
+            [Reason::Expired(NOW - 1), Reason::MissingUrl]
+        );
+    }
+
+    #[test]
+    fn no_undo_for_purged() {
+        // Every flagged link is refused by the restore request, so offering an
+        // undo or a trash entry for it would only promise what cannot work.
+        for link in [
+            utils::test_link("a", Some("https://a.b"), Some(NOW)),
+            utils::test_link("a", None, None),
+        ] {
+            assert!(!reasons(&link, NOW).is_empty());
+            assert!(undo::recreate_body(&link, &Policy::default(), NOW).is_err());
+        }
+        assert!(!Removal::Purged.keeps_in_trash());
+    }
+
+    #[test]
+    fn recording() {
+        let link = utils::test_link("a", None, None);
+        let removed = [Removed::new(&link, &[Reason::MissingUrl], NOW)];
+        let json: serde_json::Value = serde_json::from_str(&record(&removed)).unwrap();
+        assert_eq!(json[0]["key"], "a");
+        assert_eq!(json[0]["url"], serde_json::Value::Null);
+        assert_eq!(json[0]["reasons"][0], "The destination URL is missing");
+        assert_eq!(json[0]["removed_at"], NOW);
+    }
+}
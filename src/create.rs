@@ -1,13 +1,105 @@
-use chrono::DateTime;
 use dioxus::prelude::*;
 
 use crate::{
-    utils::{self, CreateRequestBody},
+    destination::UrlCheck,
+    duplicates::DuplicateWarning,
+    error::Error,
+    form::{self, FieldError, LinkForm, TtlExpiry, TtlPresets},
+    history::{self, Removal},
+    policy::use_policy,
+    qr::QrPanel,
+    query::{self, QueryBuilder},
+    settings::use_settings,
+    share::{CopyButton, SharePanel},
+    store::use_links,
+    time::{self, Expiration, TimeZoneSelect},
+    utils::{self, Link},
     Route,
 };
 
 #[component]
 pub fn Create() -> Element {
+    let mut draft = form::use_draft();
+    let mut form = use_signal(|| {
+        draft.take().unwrap_or_else(|| LinkForm {
+            time_zone: time::browser_time_zone(),
+            ..Default::default()
+        })
+    });
+    let mut variants = use_signal(|| false);
+    let mut variant_param = use_signal(|| String::from("utm_source"));
+    let mut variant_values = use_signal(String::new);
+    let mut variant_results = use_signal(Vec::<(String, Result<Box<str>, Box<str>>)>::new);
+    let mut submitted = use_signal(|| false);
+    let mut pasted = use_signal(|| false);
+    let settings = use_settings();
+    let mut created = use_signal(|| None::<Link>);
+    let mut links = use_links();
+    // The backend's answer when it refused the custom key, until the key changes.
+    let mut key_conflict = use_signal(|| None::<Box<str>>);
+    let policy = use_policy();
+    let errors = form.read().create_errors(utils::now());
+    let min_expiration = form.read().min_expiration(utils::now());
+    // Only complain about untouched fields once the user tried to submit.
+    let show = |value: &str, error: &Option<Box<str>>| {
+        if submitted() || !value.is_empty() {
+            error.clone()
+        } else {
+            None
+        }
+    };
+    let url_error = show(
+        &form.read().url,
+        &errors
+            .url
+            .clone()
+            .or_else(|| policy.read().check(&form.read().url).err()),
+    );
+    let custom_key = !form.read().key.trim().is_empty();
+    let key_taken = match &*links.read() {
+        Some(Ok(links)) => form::key_taken(&form.read().key, links),
+        _ => false,
+    };
+    let key_error = errors
+        .key
+        .clone()
+        .or_else(|| key_taken.then(|| "This key is already taken".into()))
+        .or(key_conflict());
+    let variant_urls = query::variant_urls(
+        &form.read().url,
+        &variant_param.read(),
+        &variant_values.read(),
+    );
+    let variant_error: Option<Box<str>> = if !variants() {
+        None
+    } else if variant_param.read().trim().is_empty() {
+        Some("Parameter name is required".into())
+    } else if variant_urls.is_empty() {
+        show(
+            &variant_values.read(),
+            &Some("Enter at least one value".into()),
+        )
+    } else {
+        None
+    };
+    let length_error = show(&form.read().length, &errors.length);
+    let expiration_error = show(&form.read().expiration, &errors.expiration);
+    let expiration_ttl_error = show(&form.read().expiration_ttl, &errors.expiration_ttl);
+    if let Some(link) = created() {
+        return rsx! {
+            Created {
+                link,
+                oncreateanother: move |_| {
+                    // Keep the options so a series of similar links is quick to make.
+                    let mut form = form.write();
+                    form.url.clear();
+                    form.key.clear();
+                    submitted.set(false);
+                    created.set(None);
+                },
+            }
+        };
+    }
     rsx! {
         div {
             div { class: "flex flex-col",
@@ -15,78 +107,100 @@ pub fn Create() -> Element {
             }
             form {
                 class: "mt-5",
-                onsubmit: move |evt: FormEvent| async move {
-                    let values = evt.values();
-                    let url = values["url"].as_value().to_string().into_boxed_str();
-                    let length = values["length"].as_value();
-                    let length = if length.is_empty() {
-                        None
-                    } else {
-                        match length.parse::<u16>() {
-                            Ok(l) => Some(l),
-                            Err(e) => {
-                                let window = web_sys::window().unwrap();
-                                window
-                                    .alert_with_message(format!("Invalid length {}", e).as_str())
-                                    .unwrap();
-                                return;
-                            }
+                onsubmit: move |_| async move {
+                    submitted.set(true);
+                    if variants() {
+                        let urls = query::variant_urls(
+                            &form.read().url,
+                            &variant_param.read(),
+                            &variant_values.read(),
+                        );
+                        if variant_param.read().trim().is_empty() || urls.is_empty()
+                            || policy.read().check(&form.read().url).is_err()
+                            || form.read().create_body(utils::now()).is_err()
+                        {
+                            return;
                         }
-                    };
-                    let number = values["number"].as_value().parse::<bool>().ok();
-                    let capital = values["capital"].as_value().parse::<bool>().ok();
-                    let lowercase = values["lowercase"].as_value().parse::<bool>().ok();
-                    let expiration = values["expiration"].as_value();
-                    let expiration = if expiration.is_empty() {
-                        None
-                    } else {
-                        match DateTime::parse_from_str(&expiration, "%Y-%m-%d %H:%M:%S %z") {
-                            Ok(d) => Some(d.timestamp()),
-                            Err(e) => {
-                                web_sys::window()
-                                    .unwrap()
-                                    .alert_with_message(
-                                        &format!("Invalid expiration date format, {}", e),
-                                    )
-                                    .unwrap();
-                                return;
-                            }
+                        let template = form.read().clone();
+                        variant_results.set(Vec::new());
+                        for url in urls {
+                            let variant = LinkForm {
+                                url: url.clone(),
+                                ..template.clone()
+                            };
+                            // Each variant is its own destination, so each one is checked.
+                            let body = policy
+                                .read()
+                                .check(&url)
+                                .and_then(|()| {
+                                    variant.create_body(utils::now()).map_err(|_| "Invalid form".into())
+                                });
+                            let result = match body {
+                                Ok(body) => {
+                                    history::create_link(body)
+                                        .await
+                                        .map(|short| short.key)
+                                        .map_err(|e| e.to_string().into())
+                                }
+                                Err(e) => Err(e),
+                            };
+                            variant_results.write().push((url, result));
                         }
+                        links.restart();
+                        return;
+                    }
+                    if key_taken || policy.read().check(&form.read().url).is_err() {
+                        return;
+                    }
+                    let Ok(body) = form.read().create_body(utils::now()) else {
+                        return;
                     };
-                    let expiration_ttl = values["expirationTtl"].as_value();
-                    let expiration_ttl = if expiration_ttl.is_empty() {
-                        None
-                    } else {
-                        match expiration_ttl.parse::<u32>() {
-                            Ok(e) => Some(e),
-                            Err(e) => {
-                                web_sys::window()
-                                    .unwrap()
-                                    .alert_with_message(&format!("Invalid expirationTTL, {}", e))
-                                    .unwrap();
-                                return;
-                            }
+                    let requested = body.short.clone();
+                    let custom_key = requested.is_some();
+                    let url = body.url.clone();
+                    let expiration = body
+                        .expiration
+                        .or(body.expiration_ttl.map(|ttl| utils::now() + i64::from(ttl)));
+                    match history::create_link(body).await {
+                        Ok(short) if requested.as_ref().is_some_and(|k| *k != short.key) => {
+                            // The backend ignored the requested key, so the link it made is not wanted.
+                            let stray = Link {
+                                short,
+                                url: Some(url),
+                                expiration,
+                            };
+                            let cleanup = match history::delete_link(&stray, Removal::Stray).await {
+                                Ok(()) => "It was deleted again.".to_string(),
+                                Err(e) => format!("Deleting it again failed: {}", e),
+                            };
+                            links.restart();
+                            key_conflict
+                                .set(
+                                    Some(
+                                        format!(
+                                            "The backend created {} instead of this key. {}",
+                                            stray.short.key,
+                                            cleanup,
+                                        )
+                                            .into(),
+                                    ),
+                                );
                         }
-                    };
-                    let body = CreateRequestBody {
-                        url,
-                        length,
-                        number,
-                        capital,
-                        lowercase,
-                        expiration,
-                        expiration_ttl,
-                    };
-                    let short = utils::create_link(body).await;
-                    let window = web_sys::window().unwrap();
-                    if !short.is_empty() {
-                        window
-                            .alert_with_message(format!("Link created: {short}").as_str())
-                            .unwrap();
-                        use_navigator()
-                            .push(Route::LinkItem {
-                                link: short.into(),
-                            });
+                        Ok(short) => {
+                            links.restart();
+                            created
+                                .set(
+                                    Some(Link {
+                                        short,
+                                        url: Some(url),
+                                        expiration,
+                                    }),
+                                );
+                        }
+                        Err(Error::Backend(msg)) if custom_key => {
+                            key_conflict.set(Some(format!("The backend rejected this key: {}", msg).into()));
+                        }
+                        Err(e) => utils::alert(&format!("Failed to create the link.\n\nError: {}", e)),
                     }
                 },
                 div { class: "flex flex-col w-9/12 sm:w-2/3 mx-auto",
@@ -99,8 +213,84 @@ pub fn Create() -> Element {
                                     r#type: "text",
                                     name: "url",
                                     placeholder: "URL",
-                                    required: true,
+                                    value: "{form.read().url}",
+                                    onpaste: move |_| pasted.set(true),
+                                    oninput: move |e| {
+                                        let url = e.value();
+                                        let strip = pasted() && settings.read().strip_tracking;
+                                        pasted.set(false);
+                                        form.write().url = if strip { query::strip_tracking(&url) } else { url };
+                                    },
+                                }
+                                FieldError { error: url_error }
+                                UrlCheck { url: form.read().url.clone() }
+                                DuplicateWarning { url: form.read().url.clone() }
+                                QueryBuilder {
+                                    url: form.read().url.clone(),
+                                    onchange: move |url| form.write().url = url,
+                                }
+                            }
+                            div { class: "text-xl", "Variants" }
+                            div {
+                                input {
+                                    r#type: "checkbox",
+                                    name: "variants",
+                                    cursor: "pointer",
+                                    checked: variants(),
+                                    onchange: move |e| {
+                                        if e.checked() {
+                                            form.write().key.clear();
+                                        }
+                                        variants.set(e.checked());
+                                    },
+                                }
+                            }
+                            if variants() {
+                                div { class: "text-xl", "Parameter" }
+                                div {
+                                    input {
+                                        class: "border border-gray-300 px-2",
+                                        r#type: "text",
+                                        name: "variantParam",
+                                        value: "{variant_param}",
+                                        oninput: move |e| variant_param.set(e.value()),
+                                    }
+                                }
+                                div {
+                                    p { class: "text-xl", "Values" }
+                                    p { class: "text-sm text-gray-500", "One link per line" }
+                                }
+                                div {
+                                    textarea {
+                                        class: "border border-gray-300 px-2",
+                                        rows: 4,
+                                        resize: "none",
+                                        name: "variantValues",
+                                        value: "{variant_values}",
+                                        oninput: move |e| variant_values.set(e.value()),
+                                    }
+                                    FieldError { error: variant_error.clone() }
+                                    for url in variant_urls.iter() {
+                                        p { class: "text-sm text-gray-500 break-all", "{url}" }
+                                    }
+                                }
+                            }
+                            div { class: "text-xl", "Custom key" }
+                            div {
+                                input {
+                                    class: "border border-gray-300 px-2",
+                                    r#type: "text",
+                                    name: "key",
+                                    placeholder: "Random",
+                                    disabled: variants(),
+                                    maxlength: form::MAX_KEY_LENGTH as i64,
+                                    value: "{form.read().key}",
+                                    oninput: move |e| {
+                                        key_conflict.set(None);
+                                        form.write().key = e.value();
+                                    },
                                 }
+                                FieldError { error: key_error }
                             }
                             div { class: "text-xl", "Length" }
                             div {
@@ -109,9 +299,11 @@ pub fn Create() -> Element {
                                     r#type: "text",
                                     name: "length",
                                     placeholder: "Length",
-                                    value: 6,
-                                    required: true,
+                                    disabled: custom_key,
+                                    value: "{form.read().length}",
+                                    oninput: move |e| form.write().length = e.value(),
                                 }
+                                FieldError { error: length_error }
                             }
                         }
                         div { class: "grid grid-cols-2",
@@ -121,8 +313,9 @@ pub fn Create() -> Element {
                                     r#type: "checkbox",
                                     name: "number",
                                     cursor: "pointer",
-                                    placeholder: "Number",
-                                    checked: true,
+                                    disabled: custom_key,
+                                    checked: form.read().number,
+                                    onchange: move |e| form.write().number = e.checked(),
                                 }
                             }
                             div { class: "text-xl", "Capital" }
@@ -131,7 +324,9 @@ pub fn Create() -> Element {
                                     r#type: "checkbox",
                                     name: "capital",
                                     cursor: "pointer",
-                                    checked: true,
+                                    disabled: custom_key,
+                                    checked: form.read().capital,
+                                    onchange: move |e| form.write().capital = e.checked(),
                                 }
                             }
                             div { class: "text-xl", "Lowercase" }
@@ -140,18 +335,31 @@ pub fn Create() -> Element {
                                     r#type: "checkbox",
                                     name: "lowercase",
                                     cursor: "pointer",
-                                    checked: true,
+                                    disabled: custom_key,
+                                    checked: form.read().lowercase,
+                                    onchange: move |e| form.write().lowercase = e.checked(),
                                 }
                             }
                         }
+                        FieldError { error: errors.charset.clone() }
                         div { class: "grid sm:grid-cols-2",
                             div { class: "text-xl", "Expiration" }
                             div {
                                 input {
                                     class: "border border-gray-300 px-2",
-                                    r#type: "datetime",
-                                    placeholder: "1970-01-01 00:00:00 UTC",
+                                    r#type: "datetime-local",
+                                    min: "{min_expiration}",
                                     name: "expiration",
+                                    value: "{form.read().expiration}",
+                                    oninput: move |e| form.write().expiration = e.value(),
+                                }
+                                FieldError { error: expiration_error }
+                            }
+                            div { class: "text-xl", "Time zone" }
+                            div {
+                                TimeZoneSelect {
+                                    value: form.read().time_zone.clone(),
+                                    onchange: move |zone| form.write().time_zone = zone,
                                 }
                             }
                             div { class: "text-xl", "ExpirationTTL" }
@@ -160,8 +368,13 @@ pub fn Create() -> Element {
                                     class: "border border-gray-300 px-2",
                                     r#type: "text",
                                     name: "expirationTtl",
-                                    placeholder: "Expiration TTL",
+                                    placeholder: "e.g. 90m, 7d or 2w 3d",
+                                    value: "{form.read().expiration_ttl}",
+                                    oninput: move |e| form.write().expiration_ttl = e.value(),
                                 }
+                                FieldError { error: expiration_ttl_error }
+                                TtlExpiry { ttl: form.read().expiration_ttl.clone() }
+                                TtlPresets { onselect: move |ttl| form.write().expiration_ttl = ttl }
                             }
                         }
                     }
@@ -169,10 +382,91 @@ pub fn Create() -> Element {
                         button {
                             class: "border border-gray-300 hover:bg-gray-200 px-2 text-2xl",
                             cursor: "pointer",
-                            "Create"
+                            if variants() {
+                                "Create {variant_urls.len()} links"
+                            } else {
+                                "Create"
+                            }
+                        }
+                    }
+                }
+            }
+            if !variant_results.read().is_empty() {
+                div { class: "flex flex-col w-9/12 sm:w-2/3 mx-auto mt-2",
+                    for (url , result) in variant_results.read().iter().cloned() {
+                        match result {
+                            Ok(short) => rsx! {
+                                Link {
+                                    class: "border-b border-gray-300 hover:bg-gray-100 break-all",
+                                    to: Route::LinkItem {
+                                        link: short.to_string(),
+                                    },
+                                    "{short}: {url}"
+                                }
+                            },
+                            Err(e) => rsx! {
+                                p { class: "border-b border-gray-300 text-red-500 break-all", "{url}: {e}" }
+                            },
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn Created(link: Link, oncreateanother: EventHandler) -> Element {
+    let url = link.url.clone().unwrap_or_default();
+    rsx! {
+        div {
+            div { class: "flex flex-col",
+                h1 { class: "text-3xl mx-auto", "Link created" }
+            }
+            div { class: "flex flex-col w-9/12 sm:w-2/3 mx-auto mt-5",
+                div { class: "grid sm:grid-cols-2",
+                    span { class: "text-xl", "Short URL" }
+                    div { class: "flex flex-wrap gap-1 items-start",
+                        a {
+                            class: "text-xl underline break-all",
+                            href: "{link.short.full}",
+                            target: "_blank",
+                            "{link.short.full}"
+                        }
+                        CopyButton { text: link.short.full.to_string() }
+                    }
+                    span { class: "text-xl", "Destination" }
+                    span { class: "break-all", "{url}" }
+                    span { class: "text-xl", "Expires" }
+                    span {
+                        if let Some(timestamp) = link.expiration {
+                            Expiration { timestamp }
+                        } else {
+                            "Never"
                         }
                     }
                 }
+                SharePanel { short: link.short.clone() }
+                QrPanel {
+                    text: link.short.full.clone(),
+                    name: link.short.key.clone(),
+                }
+                div { class: "flex flex-wrap gap-1 mt-2 mx-auto",
+                    button {
+                        r#type: "button",
+                        class: "border border-gray-300 hover:bg-gray-200 px-2 text-xl",
+                        cursor: "pointer",
+                        onclick: move |_| oncreateanother.call(()),
+                        "Create another"
+                    }
+                    Link {
+                        class: "border border-gray-300 hover:bg-gray-200 px-2 text-xl",
+                        to: Route::LinkItem {
+                            link: link.short.key.to_string(),
+                        },
+                        "Edit this link"
+                    }
+                }
             }
         }
     }
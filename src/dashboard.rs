@@ -0,0 +1,108 @@
+use dioxus::prelude::*;
+
+use crate::{
+    list::{self, Bucket},
+    store::use_links,
+    time,
+    utils::{self, Link},
+};
+
+const TOP_DOMAINS: usize = 10;
+
+#[derive(Debug, PartialEq)]
+pub struct Stats {
+    pub buckets: Vec<(Bucket, usize)>,
+    pub domains: Vec<(String, usize)>,
+}
+
+pub fn stats(links: &[Link], now: i64, offset: i32) -> Stats {
+    let buckets = Bucket::ALL
+        .into_iter()
+        .map(|b| {
+            (
+                b,
+                links.iter().filter(|l| b.contains(l, now, offset)).count(),
+            )
+        })
+        .collect();
+    let mut domains: Vec<(String, usize)> = Vec::new();
+    for domain in links.iter().filter_map(list::domain) {
+        match domains.iter_mut().find(|(d, _)| *d == domain) {
+            Some((_, count)) => *count += 1,
+            None => domains.push((domain, 1)),
+        }
+    }
+    domains.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    domains.truncate(TOP_DOMAINS);
+    Stats { buckets, domains }
+}
+
+#[component]
+pub fn Dashboard() -> Element {
+    let links = use_links();
+    let now = utils::now();
+    let offset = time::offset(&time::browser_time_zone(), now);
+    let stats = match &*links.read() {
+        Some(Ok(links)) => stats(links, now, offset),
+        Some(Err(e)) => return Err(e.clone().into()),
+        None => {
+            return rsx! {
+                div { class: "mb-2 text-2xl", "Loading..." }
+            }
+        }
+    };
+    rsx! {
+        div { class: "flex flex-col w-9/12 sm:w-2/3 mx-auto mt-5",
+            div { class: "grid grid-cols-2 sm:grid-cols-4 gap-2",
+                for (bucket , count) in stats.buckets {
+                    Link {
+                        class: "border border-gray-300 hover:bg-gray-100 p-2",
+                        to: bucket.route(""),
+                        p { class: "text-3xl", "{count}" }
+                        p { class: "text-sm text-gray-500", "{bucket.label()}" }
+                    }
+                }
+            }
+            h2 { class: "text-xl mt-5", "Top destination domains" }
+            if stats.domains.is_empty() {
+                p { class: "text-gray-500", "No links yet." }
+            }
+            for (domain , count) in stats.domains {
+                Link {
+                    class: "flex justify-between border-b border-gray-300 hover:bg-gray-100 px-1",
+                    to: Bucket::All.route(&domain),
+                    span { class: "break-all", "{domain}" }
+                    span { "{count}" }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_792_413_000;
+
+    #[test]
+    fn counting() {
+        let links = [
+            utils::test_link("a", Some("https://a.b/1"), None),
+            utils::test_link("b", Some("https://a.b/2"), Some(NOW - 10)),
+            utils::test_link("c", Some("https://c.d"), Some(NOW + 60)),
+            utils::test_link("d", None, None),
+        ];
+        let stats = stats(&links, NOW, 0);
+        let count = |bucket| stats.buckets.iter().find(|(b, _)| *b == bucket).unwrap().1;
+        assert_eq!(count(Bucket::All), 4);
+        assert_eq!(count(Bucket::WithExpiry), 2);
+        assert_eq!(count(Bucket::NoExpiry), 2);
+        assert_eq!(count(Bucket::Expired), 1);
+        assert_eq!(count(Bucket::Today), 1);
+        assert_eq!(
+            stats.domains,
+            [("a.b".to_string(), 2), ("c.d".to_string(), 1)]
+        );
+    }
+}
@@ -0,0 +1,309 @@
+use std::{fmt, net::Ipv4Addr};
+
+use dioxus::prelude::*;
+
+use crate::{
+    store::use_links,
+    utils::{self, Link},
+};
+
+// Schemes that run code or embed content instead of pointing somewhere.
+const DANGEROUS_SCHEMES: [&str; 4] = ["javascript", "data", "vbscript", "file"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Warning {
+    IpLiteral,
+    MixedScript(Box<str>),
+    Scheme(Box<str>),
+    Credentials,
+    ShortLink(Box<str>),
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warning::IpLiteral => write!(f, "The host is an IP address instead of a domain name"),
+            Warning::MixedScript(host) => write!(
+                f,
+                "The host {host} mixes alphabets, it may imitate another domain"
+            ),
+            Warning::Scheme(scheme) => write!(
+                f,
+                "{scheme}: URLs can run code or embed content in the visitor's browser"
+            ),
+            Warning::Credentials => write!(f, "The URL contains a username or password"),
+            Warning::ShortLink(key) => write!(
+                f,
+                "This is the short link {key} on this backend, chaining short links can redirect in a loop"
+            ),
+        }
+    }
+}
+
+struct Parts<'a> {
+    scheme: &'a str,
+    userinfo: Option<&'a str>,
+    host: &'a str,
+    port: Option<&'a str>,
+    rest: &'a str,
+}
+
+fn is_scheme(scheme: &str) -> bool {
+    scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+}
+
+// Splits off a scheme without `//`, like `mailto:` or `javascript:`. A
+// `host:port` is not mistaken for one.
+fn opaque_scheme(url: &str) -> Option<&str> {
+    let (scheme, rest) = url.split_once(':')?;
+    let port = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let is_port = !port.is_empty() && port.chars().all(|c| c.is_ascii_digit());
+    (is_scheme(scheme) && !is_port && !rest.starts_with("//")).then_some(scheme)
+}
+
+fn split(url: &str) -> Option<Parts<'_>> {
+    let (scheme, rest) = url.split_once("://")?;
+    if !is_scheme(scheme) {
+        return None;
+    }
+    let end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let (authority, rest) = rest.split_at(end);
+    let (userinfo, host_port) = match authority.rsplit_once('@') {
+        Some((userinfo, host_port)) => (Some(userinfo), host_port),
+        None => (None, authority),
+    };
+    let (host, port) = if host_port.starts_with('[') {
+        let close = host_port.find(']')?;
+        let (host, port) = host_port.split_at(close + 1);
+        (host, port.strip_prefix(':'))
+    } else {
+        match host_port.rsplit_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (host_port, None),
+        }
+    };
+    if host.is_empty() || port.is_some_and(|p| p.parse::<u16>().is_err()) {
+        return None;
+    }
+    Some(Parts {
+        scheme,
+        userinfo,
+        host,
+        port,
+        rest,
+    })
+}
+
+/// Trims the URL, adds `https://` when the scheme is missing, and writes the
+/// host in lowercase ASCII, converting international domain names to punycode.
+pub fn normalize(url: &str) -> Result<Box<str>, Box<str>> {
+    let url = url.trim();
+    if url.is_empty() {
+        return Err("URL is required".into());
+    }
+    if url.contains(char::is_whitespace) {
+        return Err("URL must not contain whitespace".into());
+    }
+    if let Some(scheme) = opaque_scheme(url) {
+        return Ok(format!("{}{}", scheme.to_ascii_lowercase(), &url[scheme.len()..]).into());
+    }
+    let with_scheme;
+    let url = if url.contains("://") {
+        url
+    } else {
+        with_scheme = format!("https://{}", url.trim_start_matches('/'));
+        &with_scheme
+    };
+    let parts = split(url).ok_or("URL must look like https://example.com/path")?;
+    let host = if parts.host.starts_with('[') {
+        parts.host.to_ascii_lowercase()
+    } else {
+        idna::domain_to_ascii(parts.host).map_err(|_| "The domain name is not valid")?
+    };
+    let mut normalized = format!("{}://", parts.scheme.to_ascii_lowercase());
+    if let Some(userinfo) = parts.userinfo {
+        normalized.push_str(userinfo);
+        normalized.push('@');
+    }
+    normalized.push_str(&host);
+    if let Some(port) = parts.port {
+        normalized.push(':');
+        normalized.push_str(port);
+    }
+    normalized.push_str(parts.rest);
+    Ok(normalized.into())
+}
+
+/// The host of a URL produced by [`normalize`], without the port.
+pub fn host(url: &str) -> Option<&str> {
+    split(url).map(|parts| parts.host)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Script {
+    Latin,
+    Greek,
+    Cyrillic,
+}
+
+fn script(c: char) -> Option<Script> {
+    match c {
+        'a'..='z' | 'A'..='Z' | '\u{c0}'..='\u{24f}' => Some(Script::Latin),
+        '\u{370}'..='\u{3ff}' => Some(Script::Greek),
+        '\u{400}'..='\u{52f}' => Some(Script::Cyrillic),
+        _ => None,
+    }
+}
+
+fn mixed_script(label: &str) -> bool {
+    let mut scripts = label.chars().filter_map(script);
+    match scripts.next() {
+        Some(first) => scripts.any(|s| s != first),
+        None => false,
+    }
+}
+
+fn host_port(parts: &Parts) -> String {
+    match parts.port {
+        Some(port) => format!("{}:{}", parts.host, port),
+        None => parts.host.to_string(),
+    }
+}
+
+/// The hosts short URLs are served from: those of the loaded links, and the
+/// backend itself when there are none yet.
+pub fn short_hosts(links: &[Link], backend: Option<String>) -> Vec<String> {
+    let mut hosts: Vec<String> = Vec::new();
+    for host in links
+        .iter()
+        .filter_map(|l| split(&l.short.full).map(|p| host_port(&p).to_ascii_lowercase()))
+        .chain(backend.map(|b| b.to_ascii_lowercase()))
+    {
+        if !hosts.contains(&host) {
+            hosts.push(host);
+        }
+    }
+    hosts
+}
+
+/// Warnings about a URL produced by [`normalize`]. `short_hosts` are the
+/// hosts short URLs of this backend are served from.
+pub fn warnings(url: &str, short_hosts: &[String]) -> Vec<Warning> {
+    let mut warnings = Vec::new();
+    let scheme = opaque_scheme(url).or_else(|| url.split_once("://").map(|(s, _)| s));
+    if let Some(scheme) = scheme.filter(|s| DANGEROUS_SCHEMES.contains(s)) {
+        warnings.push(Warning::Scheme(scheme.into()));
+    }
+    let Some(parts) = split(url) else {
+        return warnings;
+    };
+    if parts.userinfo.is_some() {
+        warnings.push(Warning::Credentials);
+    }
+    if parts.host.starts_with('[') || parts.host.parse::<Ipv4Addr>().is_ok() {
+        warnings.push(Warning::IpLiteral);
+    }
+    let (unicode, _) = idna::domain_to_unicode(parts.host);
+    if unicode.split('.').any(mixed_script) {
+        warnings.push(Warning::MixedScript(unicode.into()));
+    }
+    let host_port = host_port(&parts);
+    if short_hosts
+        .iter()
+        .any(|h| h.eq_ignore_ascii_case(&host_port))
+    {
+        let key = parts.rest.trim_start_matches('/');
+        let key = key.split(['/', '?', '#']).next().unwrap_or_default();
+        warnings.push(Warning::ShortLink(key.into()));
+    }
+    warnings
+}
+
+#[component]
+pub fn UrlCheck(url: String) -> Element {
+    let Ok(normalized) = normalize(&url) else {
+        return rsx! {};
+    };
+    let links = use_links();
+    let hosts = match &*links.read() {
+        Some(Ok(links)) => short_hosts(links, utils::backend_host()),
+        _ => short_hosts(&[], utils::backend_host()),
+    };
+    let warnings = warnings(&normalized, &hosts);
+    rsx! {
+        if normalized.as_ref() != url.trim() {
+            p { class: "text-sm text-gray-500 break-all", "Will be saved as {normalized}" }
+        }
+        for warning in warnings {
+            p { class: "text-sm text-yellow-600", "{warning}" }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ok(url: &str) -> String {
+        normalize(url).unwrap().into()
+    }
+
+    #[test]
+    fn normalizing() {
+        assert_eq!(ok(" https://a.b/c "), "https://a.b/c");
+        assert_eq!(ok("example.com/Path?Q=1"), "https://example.com/Path?Q=1");
+        assert_eq!(ok("HTTP://Example.COM:8080/"), "http://example.com:8080/");
+        assert_eq!(ok("localhost:3000"), "https://localhost:3000");
+        assert_eq!(ok("https://bücher.de/ü"), "https://xn--bcher-kva.de/ü");
+        assert_eq!(ok("https://u:p@A.b"), "https://u:p@a.b");
+        assert_eq!(ok("https://[::1]:80/x"), "https://[::1]:80/x");
+        assert_eq!(ok("mailto:a@b.c"), "mailto:a@b.c");
+        assert!(normalize("").is_err());
+        assert!(normalize("https://").is_err());
+        assert!(normalize("https://a b").is_err());
+        assert!(normalize("https://a.b:port").is_err());
+    }
+
+    #[test]
+    fn warning() {
+        let hosts = ["s.example".to_string()];
+        let check = |url: &str| warnings(&normalize(url).unwrap(), &hosts);
+        assert_eq!(check("https://example.com/a"), []);
+        assert_eq!(check("bücher.de"), []);
+        assert_eq!(check("http://127.0.0.1/"), [Warning::IpLiteral]);
+        assert_eq!(check("http://[::1]/"), [Warning::IpLiteral]);
+        assert_eq!(check("https://user:pw@a.b"), [Warning::Credentials]);
+        assert_eq!(
+            check("javascript:alert(1)"),
+            [Warning::Scheme("javascript".into())]
+        );
+        assert_eq!(check("data:text/html,hi"), [Warning::Scheme("data".into())]);
+        // The first "а" is Cyrillic.
+        assert_eq!(
+            check("https://pаypal.com"),
+            [Warning::MixedScript("pаypal.com".into())]
+        );
+        assert_eq!(
+            check("https://S.example/abc?x=1"),
+            [Warning::ShortLink("abc".into())]
+        );
+    }
+
+    #[test]
+    fn short_link_hosts() {
+        let mut link = utils::test_link("abc", None, None);
+        link.short.full = "https://Go.Example/abc".into();
+        let hosts = short_hosts(&[link], Some("api.example:8080".into()));
+        assert_eq!(hosts, ["go.example", "api.example:8080"]);
+        let check = |url: &str| warnings(&normalize(url).unwrap(), &hosts);
+        assert_eq!(check("go.example/xyz"), [Warning::ShortLink("xyz".into())]);
+        assert_eq!(
+            check("http://api.example:8080/k"),
+            [Warning::ShortLink("k".into())]
+        );
+        assert_eq!(check("https://api.example/k"), []);
+    }
+}
@@ -0,0 +1,262 @@
+use dioxus::prelude::*;
+
+use crate::{
+    destination,
+    history::{self, Removal},
+    store::use_links,
+    time::Expiration,
+    undo::{self, Undo},
+    utils::{self, Link},
+    Route,
+};
+
+/// The form two URLs share when they lead to the same place. Falls back to
+/// the trimmed URL when it cannot be normalized.
+pub fn destination_key(url: &str) -> String {
+    let url = destination::normalize(url)
+        .map(String::from)
+        .unwrap_or_else(|_| url.trim().to_string());
+    // `https://a.b` and `https://a.b/` are the same page.
+    match url.split_once("://") {
+        Some((_, rest)) if !rest.contains(['/', '?', '#']) => format!("{}/", url),
+        _ => url,
+    }
+}
+
+pub fn same_destination<'a>(url: &str, links: &'a [Link]) -> Vec<&'a Link> {
+    let key = destination_key(url);
+    links
+        .iter()
+        .filter(|l| l.url.as_deref().is_some_and(|u| destination_key(u) == key))
+        .collect()
+}
+
+/// Destinations with more than one link, the largest groups first.
+pub fn groups(links: &[Link]) -> Vec<(String, Vec<&Link>)> {
+    let mut groups: Vec<(String, Vec<&Link>)> = Vec::new();
+    for link in links {
+        let Some(url) = &link.url else {
+            continue;
+        };
+        let key = destination_key(url);
+        match groups.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, group)) => group.push(link),
+            None => groups.push((key, vec![link])),
+        }
+    }
+    groups.retain(|(_, group)| group.len() > 1);
+    groups.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(&b.0)));
+    groups
+}
+
+#[component]
+pub fn DuplicateWarning(url: String) -> Element {
+    let links = use_links();
+    if url.trim().is_empty() {
+        return rsx! {};
+    }
+    let existing: Vec<Link> = match &*links.read() {
+        Some(Ok(links)) => same_destination(&url, links).into_iter().cloned().collect(),
+        _ => Vec::new(),
+    };
+    rsx! {
+        if !existing.is_empty() {
+            p { class: "text-sm text-yellow-600",
+                "This destination already has short links: "
+                for link in existing {
+                    Link {
+                        class: "underline mr-1",
+                        to: Route::LinkItem {
+                            link: link.short.key.to_string(),
+                        },
+                        "{link.short.key}"
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn Duplicates() -> Element {
+    let mut links = use_links();
+    let mut deleting = use_signal(|| false);
+    let toast = undo::use_undo();
+    let groups: Vec<(String, Vec<Link>)> = match &*links.read() {
+        Some(Ok(links)) => groups(links)
+            .into_iter()
+            .map(|(url, group)| (url, group.into_iter().cloned().collect()))
+            .collect(),
+        Some(Err(e)) => return Err(e.clone().into()),
+        None => {
+            return rsx! {
+                div { class: "mb-2 text-2xl", "Loading..." }
+            }
+        }
+    };
+    rsx! {
+        div {
+            div { class: "flex flex-col",
+                h1 { class: "text-3xl mx-auto", "Duplicate destinations" }
+            }
+            div { class: "flex flex-col w-9/12 sm:w-2/3 mx-auto mt-5",
+                if groups.is_empty() {
+                    p { class: "text-gray-500", "Every destination has a single link." }
+                }
+                for (url , group) in groups {
+                    div { class: "border-b border-gray-300 py-2",
+                        p { class: "text-xl break-all", "{url}" }
+                        for (i , link) in group.iter().cloned().enumerate() {
+                            div { class: "flex flex-wrap gap-1 items-center mt-1",
+                                Link {
+                                    class: "underline flex-1 break-all",
+                                    to: Route::LinkItem {
+                                        link: link.short.key.to_string(),
+                                    },
+                                    "{link.short.key}"
+                                }
+                                span { class: "text-sm text-gray-500",
+                                    if let Some(timestamp) = link.expiration {
+                                        "expires "
+                                        Expiration { timestamp }
+                                    } else {
+                                        "never expires"
+                                    }
+                                }
+                                Link {
+                                    class: "border border-gray-300 hover:bg-gray-200 px-1",
+                                    to: Route::LinkItem {
+                                        link: link.short.key.to_string(),
+                                    },
+                                    "Re-point"
+                                }
+                                button {
+                                    r#type: "button",
+                                    class: "border border-gray-300 hover:bg-gray-200 px-1 text-red-500",
+                                    cursor: "pointer",
+                                    disabled: deleting(),
+                                    onclick: {
+                                        let link = link.clone();
+                                        move |_| {
+                                            let link = link.clone();
+                                            async move {
+                                                let key = &link.short.key;
+                                                if !utils::confirm(&format!("Delete {}?", key)) {
+                                                    return;
+                                                }
+                                                deleting.set(true);
+                                                match history::delete_link(&link, Removal::Deleted).await {
+                                                    Ok(()) => {
+                                                        undo::offer(
+                                                            toast,
+                                                            format!("Deleted {}", key),
+                                                            vec![Undo::Recreate(link.clone())],
+                                                        )
+                                                    }
+                                                    Err(e) => {
+                                                        utils::alert(&format!("Failed to delete {}.\n\nError: {}", key, e))
+                                                    }
+                                                }
+                                                deleting.set(false);
+                                                links.restart();
+                                            }
+                                        }
+                                    },
+                                    "Delete"
+                                }
+                                if i == 0 {
+                                    button {
+                                        r#type: "button",
+                                        class: "border border-gray-300 hover:bg-gray-200 px-1 text-red-500",
+                                        cursor: "pointer",
+                                        disabled: deleting(),
+                                        onclick: {
+                                            let extras: Vec<Link> = group.iter().skip(1).cloned().collect();
+                                            let keep = link.short.key.clone();
+                                            move |_| {
+                                                let (extras, keep) = (extras.clone(), keep.clone());
+                                                async move {
+                                                    if !utils::confirm(
+                                                        &format!(
+                                                            "Keep {} and delete {}?",
+                                                            keep,
+                                                            extras
+                                                                .iter()
+                                                                .map(|l| l.short.key.as_ref())
+                                                                .collect::<Vec<_>>()
+                                                                .join(", "),
+                                                        ),
+                                                    ) {
+                                                        return;
+                                                    }
+                                                    deleting.set(true);
+                                                    let mut failed = Vec::new();
+                                                    let mut deleted = Vec::new();
+                                                    for link in extras {
+                                                        match history::delete_link(&link, Removal::Deleted).await {
+                                                            Ok(()) => deleted.push(Undo::Recreate(link)),
+                                                            Err(e) => failed.push(format!("{}: {}", link.short.key, e)),
+                                                        }
+                                                    }
+                                                    deleting.set(false);
+                                                    links.restart();
+                                                    if !deleted.is_empty() {
+                                                        undo::offer(toast, format!("Deleted {} links", deleted.len()), deleted);
+                                                    }
+                                                    if !failed.is_empty() {
+                                                        utils::alert(
+                                                            &format!("Some links were not deleted.\n\n{}", failed.join("\n")),
+                                                        );
+                                                    }
+                                                }
+                                            }
+                                        },
+                                        "Keep only this one"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys() {
+        assert_eq!(destination_key("HTTPS://A.b"), "https://a.b/");
+        assert_eq!(destination_key(" a.b/ "), "https://a.b/");
+        assert_eq!(destination_key("https://a.b/x?y=1"), "https://a.b/x?y=1");
+        assert_eq!(destination_key("mailto:x@a.b"), "mailto:x@a.b");
+    }
+
+    #[test]
+    fn grouping() {
+        let links = [
+            utils::test_link("a", Some("https://a.b"), None),
+            utils::test_link("b", Some("https://c.d/"), None),
+            utils::test_link("c", Some("A.b/"), None),
+            utils::test_link("d", None, None),
+            utils::test_link("e", Some("https://a.b/"), None),
+            utils::test_link("f", Some("https://c.d"), None),
+            utils::test_link("g", Some("https://x.y"), None),
+        ];
+        let keys = |group: &[&Link]| {
+            group
+                .iter()
+                .map(|l| l.short.key.to_string())
+                .collect::<Vec<_>>()
+        };
+        let groups = groups(&links);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].0, "https://a.b/");
+        assert_eq!(keys(&groups[0].1), ["a", "c", "e"]);
+        assert_eq!(keys(&groups[1].1), ["b", "f"]);
+        assert_eq!(keys(&same_destination("a.b", &links)), ["a", "c", "e"]);
+    }
+}
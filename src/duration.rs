@@ -0,0 +1,102 @@
+const UNITS: [(char, u64); 6] = [
+    ('y', 365 * 24 * 60 * 60),
+    ('w', 7 * 24 * 60 * 60),
+    ('d', 24 * 60 * 60),
+    ('h', 60 * 60),
+    ('m', 60),
+    ('s', 1),
+];
+
+/// Parses durations such as `90m`, `7d` or `2w 3d` into seconds. A bare
+/// number is taken as seconds, like the backend's `expirationTtl`.
+pub fn parse_duration(duration: &str) -> Result<u32, Box<str>> {
+    let duration = duration.trim();
+    if let Ok(seconds) = duration.parse::<u32>() {
+        return Ok(seconds);
+    }
+    let mut total: u64 = 0;
+    let mut number = String::new();
+    let mut units = 0;
+    // A number may be spaced from its unit, but not split, so `1 2d` is no `12d`.
+    let mut spaced = false;
+    for c in duration.chars() {
+        if c.is_ascii_digit() {
+            if spaced {
+                return Err(format!("Missing unit after '{}'", number).into());
+            }
+            number.push(c);
+            continue;
+        }
+        if c.is_whitespace() {
+            spaced = !number.is_empty();
+            continue;
+        }
+        spaced = false;
+        let Some((_, seconds)) = UNITS.iter().find(|(u, _)| *u == c.to_ascii_lowercase()) else {
+            return Err(format!("Unknown unit '{}', use y, w, d, h, m or s", c).into());
+        };
+        let value = number
+            .parse::<u64>()
+            .map_err(|_| format!("Missing number before '{}'", c))?;
+        total = total.saturating_add(value.saturating_mul(*seconds));
+        number.clear();
+        units += 1;
+    }
+    if !number.is_empty() {
+        return Err(format!("Missing unit after '{}'", number).into());
+    }
+    if units == 0 {
+        return Err("Duration is empty".into());
+    }
+    u32::try_from(total).map_err(|_| "Duration is too long".into())
+}
+
+pub fn format_duration(seconds: u32) -> String {
+    let mut rest = u64::from(seconds);
+    let mut parts = Vec::new();
+    for (unit, size) in UNITS {
+        if rest >= size {
+            parts.push(format!("{}{}", rest / size, unit));
+            rest %= size;
+        }
+    }
+    if parts.is_empty() {
+        return String::from("0s");
+    }
+    parts.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(parse_duration("3600"), Ok(3600));
+        assert_eq!(parse_duration("90m"), Ok(5400));
+        assert_eq!(parse_duration("7d"), Ok(604_800));
+        assert_eq!(parse_duration("2w 3d"), Ok(1_468_800));
+        assert_eq!(parse_duration("1H30M"), Ok(5400));
+        assert_eq!(parse_duration("1y"), Ok(31_536_000));
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("d").is_err());
+        assert!(parse_duration("5").is_ok());
+        assert!(parse_duration("5 days").is_err());
+        assert!(parse_duration("1d 5").is_err());
+        assert!(parse_duration("1 2d").is_err());
+        assert!(parse_duration("1d 2 3h").is_err());
+        assert_eq!(parse_duration("2 w 3 d"), Ok(1_468_800));
+        assert!(parse_duration("200y").is_err());
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(format_duration(0), "0s");
+        assert_eq!(format_duration(5400), "1h 30m");
+        assert_eq!(format_duration(1_468_800), "2w 3d");
+        assert_eq!(format_duration(2_592_000), "4w 2d");
+        for seconds in [59, 3600, 86_401, 31_536_000] {
+            assert_eq!(parse_duration(&format_duration(seconds)), Ok(seconds));
+        }
+    }
+}
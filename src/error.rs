@@ -0,0 +1,112 @@
+use std::fmt;
+
+use web_sys::wasm_bindgen::JsValue;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    NoWindow,
+    NoStorage,
+    NotLoggedIn,
+    Js(Box<str>),
+    Request(Box<str>),
+    Backend(Box<str>),
+    MissingData,
+    Json(Box<str>),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NoWindow => write!(f, "The browser window is not available"),
+            Error::NoStorage => write!(f, "Local storage is not available"),
+            Error::NotLoggedIn => write!(f, "Login information is missing"),
+            Error::Js(e) => write!(f, "Browser error: {e}"),
+            Error::Request(e) => write!(f, "Request failed: {e}"),
+            Error::Backend(msg) => write!(f, "{msg}"),
+            Error::MissingData => write!(f, "The backend response contained no data"),
+            Error::Json(e) => write!(f, "Invalid JSON: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<JsValue> for Error {
+    fn from(value: JsValue) -> Self {
+        let msg = value
+            .as_string()
+            .unwrap_or_else(|| format!("{:?}", value))
+            .into_boxed_str();
+        Error::Js(msg)
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(value: reqwest::Error) -> Self {
+        Error::Request(value.to_string().into_boxed_str())
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(value: serde_json::Error) -> Self {
+        Error::Json(value.to_string().into_boxed_str())
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+const PANIC_SCREEN: &str = r#"<div style="font-family: sans-serif; max-width: 48rem; margin: 4rem auto; padding: 0 1rem;">
+    <h1 style="font-size: 1.875rem;">Something went wrong</h1>
+    <p>linkrusk ran into an unexpected error and had to stop. Reloading the page usually fixes it.</p>
+    <p>If it keeps happening, please copy the details below into a bug report.</p>
+    <textarea id="linkrusk-panic-details" readonly rows="12" style="width: 100%; border: 1px solid #d1d5db; font-family: monospace;"></textarea>
+    <div style="margin-top: 0.5rem;">
+        <button style="border: 1px solid #d1d5db; padding: 0 0.5rem; cursor: pointer;" onclick="navigator.clipboard.writeText(document.getElementById('linkrusk-panic-details').value)">Copy details</button>
+        <button style="border: 1px solid #d1d5db; padding: 0 0.5rem; cursor: pointer;" onclick="location.reload()">Reload</button>
+        <button style="border: 1px solid #d1d5db; padding: 0 0.5rem; cursor: pointer; color: #ef4444;" onclick="localStorage.removeItem('backendUrl'); localStorage.removeItem('token'); location.reload()">Logout and reload</button>
+    </div>
+</div>"#;
+
+/// Replaces the page with a static error screen whenever the app panics.
+///
+/// The WASM instance is unusable after a panic, so the screen only relies on
+/// inline JavaScript. The previous hook still runs, which keeps the panic
+/// message in the console.
+pub fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        previous(info);
+        let details = panic_details(info);
+        // Nothing sensible is left to do if this fails as well.
+        let _ = render_panic_screen(&details);
+    }));
+}
+
+fn panic_details(info: &std::panic::PanicHookInfo) -> String {
+    let window = web_sys::window();
+    let href = window
+        .as_ref()
+        .and_then(|w| w.location().href().ok())
+        .unwrap_or_default();
+    let user_agent = window
+        .as_ref()
+        .and_then(|w| w.navigator().user_agent().ok())
+        .unwrap_or_default();
+    format!(
+        "linkrusk {}\nPage: {}\nUser agent: {}\n\n{}",
+        env!("CARGO_PKG_VERSION"),
+        href,
+        user_agent,
+        info
+    )
+}
+
+fn render_panic_screen(details: &str) -> Option<()> {
+    let document = web_sys::window()?.document()?;
+    let body = document.body()?;
+    body.set_inner_html(PANIC_SCREEN);
+    document
+        .get_element_by_id("linkrusk-panic-details")?
+        .set_text_content(Some(details));
+    Some(())
+}
@@ -0,0 +1,351 @@
+use dioxus::prelude::*;
+
+use crate::{
+    calendar::reschedule_body,
+    duration,
+    form::{self, FieldError},
+    history,
+    policy::use_policy,
+    store::use_links,
+    time::{self, Expiration, TimeZoneSelect},
+    undo::{self, Undo},
+    utils::{self, Link, UpdateRequestBody},
+    Route,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Extension {
+    By(u32),
+    To(i64),
+}
+
+impl Extension {
+    /// Extending an already expired link counts from now, not from the past.
+    pub fn apply(self, expiration: i64, now: i64) -> i64 {
+        match self {
+            Extension::By(seconds) => expiration.max(now) + i64::from(seconds),
+            Extension::To(timestamp) => timestamp,
+        }
+    }
+}
+
+pub fn in_window(link: &Link, window: u32, include_expired: bool, now: i64) -> bool {
+    link.expiration
+        .is_some_and(|e| e <= now + i64::from(window) && (include_expired || e > now))
+}
+
+/// Only the expiration changes, so like any change that keeps the destination
+/// it is not held to the domain policy. The caller warns about breaking ones.
+pub fn plan_update(
+    link: &Link,
+    extension: Extension,
+    now: i64,
+) -> Result<UpdateRequestBody, Box<str>> {
+    let expiration = link.expiration.ok_or("The link never expires")?;
+    reschedule_body(link, extension.apply(expiration, now), now)
+}
+
+#[component]
+pub fn Extend() -> Element {
+    let mut links = use_links();
+    let policy = use_policy();
+    let mut window = use_signal(|| String::from("30d"));
+    let mut include_expired = use_signal(|| false);
+    let mut to_date = use_signal(|| false);
+    let mut by = use_signal(|| String::from("30d"));
+    let mut date = use_signal(String::new);
+    let mut time_zone = use_signal(time::browser_time_zone);
+    let mut deselected = use_signal(Vec::<Box<str>>::new);
+    let mut running = use_signal(|| false);
+    let toast = undo::use_undo();
+    let mut report = use_signal(Vec::<(Box<str>, Result<i64, Box<str>>)>::new);
+    let now = utils::now();
+    let window_seconds = duration::parse_duration(&window.read());
+    let extension = if to_date() {
+        form::parse_expiration(&date.read(), &time_zone.read(), now)
+            .and_then(|t| t.ok_or("Pick the new expiration date".into()))
+            .map(Extension::To)
+    } else {
+        duration::parse_duration(&by.read()).map(Extension::By)
+    };
+    let candidates: Vec<Link> = match (&*links.read(), &window_seconds) {
+        (Some(Ok(links)), Ok(window)) => {
+            let mut candidates: Vec<Link> = links
+                .iter()
+                .filter(|l| in_window(l, *window, include_expired(), now))
+                .cloned()
+                .collect();
+            candidates.sort_by_key(|l| l.expiration);
+            candidates
+        }
+        (Some(Err(e)), _) => return Err(e.clone().into()),
+        _ => Vec::new(),
+    };
+    let plan: Vec<_> = candidates
+        .into_iter()
+        .map(|link| {
+            let selected = !deselected.read().contains(&link.short.key);
+            let body = match extension {
+                Ok(extension) => plan_update(&link, extension, now),
+                Err(_) => Err("Choose how to extend".into()),
+            };
+            let warning = link
+                .url
+                .as_deref()
+                .and_then(|url| policy.read().check(url).err());
+            (link, selected, body, warning)
+        })
+        .collect();
+    let extension_error = extension.clone().err();
+    let window_error = window_seconds.clone().err();
+    let ready = plan
+        .iter()
+        .filter(|(_, selected, body, _)| *selected && body.is_ok())
+        .count();
+    rsx! {
+        div {
+            div { class: "flex flex-col",
+                h1 { class: "text-3xl mx-auto", "Extend expiring links" }
+            }
+            div { class: "flex flex-col w-9/12 sm:w-2/3 mx-auto mt-5",
+                div { class: "grid sm:grid-cols-2 gap-1",
+                    span { class: "text-xl", "Expiring within" }
+                    div {
+                        input {
+                            class: "border border-gray-300 px-2",
+                            r#type: "text",
+                            value: "{window}",
+                            oninput: move |e| window.set(e.value()),
+                        }
+                        FieldError { error: window_error }
+                        label { class: "block", cursor: "pointer",
+                            input {
+                                r#type: "checkbox",
+                                checked: include_expired(),
+                                onchange: move |e| include_expired.set(e.checked()),
+                            }
+                            " Include already expired links"
+                        }
+                    }
+                    span { class: "text-xl", "Extend" }
+                    div {
+                        select {
+                            class: "border border-gray-300 px-2",
+                            cursor: "pointer",
+                            onchange: move |e| to_date.set(e.value() == "to"),
+                            option { value: "by", selected: !to_date(), "by a duration" }
+                            option { value: "to", selected: to_date(), "to a fixed date" }
+                        }
+                        if to_date() {
+                            input {
+                                class: "border border-gray-300 px-2 block",
+                                r#type: "datetime-local",
+                                value: "{date}",
+                                oninput: move |e| date.set(e.value()),
+                            }
+                            TimeZoneSelect {
+                                value: time_zone(),
+                                onchange: move |zone| time_zone.set(zone),
+                            }
+                        } else {
+                            input {
+                                class: "border border-gray-300 px-2 block",
+                                r#type: "text",
+                                placeholder: "e.g. 30d",
+                                value: "{by}",
+                                oninput: move |e| by.set(e.value()),
+                            }
+                        }
+                        FieldError { error: extension_error }
+                    }
+                }
+                table { class: "table-auto w-full mt-2",
+                    thead {
+                        tr { class: "border-b border-gray-300 text-left",
+                            th {}
+                            th { "Key" }
+                            th { "Now expires" }
+                            th { "Will expire" }
+                        }
+                    }
+                    tbody {
+                        for (link , selected , body , warning) in plan {
+                            tr { class: "border-b border-gray-300 align-top",
+                                td {
+                                    input {
+                                        r#type: "checkbox",
+                                        checked: selected,
+                                        disabled: body.is_err(),
+                                        onchange: {
+                                            let key = link.short.key.clone();
+                                            move |e: FormEvent| {
+                                                if e.checked() {
+                                                    deselected.write().retain(|k| *k != key);
+                                                } else {
+                                                    deselected.write().push(key.clone());
+                                                }
+                                            }
+                                        },
+                                    }
+                                }
+                                td { class: "break-all",
+                                    Link {
+                                        class: "underline",
+                                        to: Route::LinkItem {
+                                            link: link.short.key.to_string(),
+                                        },
+                                        "{link.short.key}"
+                                    }
+                                    if let Some(warning) = warning {
+                                        p { class: "text-sm text-yellow-600",
+                                            "{warning}, the destination is kept as it is"
+                                        }
+                                    }
+                                }
+                                td {
+                                    if let Some(timestamp) = link.expiration {
+                                        Expiration { timestamp }
+                                    }
+                                }
+                                td {
+                                    match &body {
+                                        Ok(body) => rsx! {
+                                            if let Some(timestamp) = body.expiration {
+                                                Expiration { timestamp }
+                                            }
+                                        },
+                                        Err(e) => rsx! {
+                                            span { class: "text-red-500", "{e}" }
+                                        },
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                div { class: "mt-2 mx-auto",
+                    button {
+                        class: "border border-gray-300 hover:bg-gray-200 px-2 text-2xl",
+                        cursor: "pointer",
+                        disabled: running() || ready == 0,
+                        onclick: move |_| {
+                            let extension = extension.clone();
+                            let window_seconds = window_seconds.clone();
+                            async move {
+                                let now = utils::now();
+                                let Ok(extension) = extension else {
+                                    return;
+                                };
+                                let bodies: Vec<(Link, UpdateRequestBody)> = match &*links.read() {
+                                    Some(Ok(links)) => {
+                                        links
+                                            .iter()
+                                            .filter(|l| {
+                                                !deselected.read().contains(&l.short.key)
+                                                    && window_seconds
+                                                        .as_ref()
+                                                        .is_ok_and(|w| in_window(l, *w, include_expired(), now))
+                                            })
+                                            .filter_map(|l| {
+                                                plan_update(l, extension, now)
+                                                    .ok()
+                                                    .map(|body| (l.clone(), body))
+                                            })
+                                            .collect()
+                                    }
+                                    _ => return,
+                                };
+                                if !utils::confirm(&format!("Extend {} links?", bodies.len())) {
+                                    return;
+                                }
+                                running.set(true);
+                                report.set(Vec::new());
+                                let mut extended = Vec::new();
+                                for (link, body) in bodies {
+                                    let key = body.short.clone();
+                                    let expiration = body.expiration.unwrap_or_default();
+                                    let revert = Undo::revert(link.clone(), &body, now);
+                                    let result = history::update_link(&link, body)
+                                        .await
+                                        .map(|()| expiration)
+                                        .map_err(|e| e.to_string().into());
+                                    if result.is_ok() {
+                                        extended.push(revert);
+                                    }
+                                    report.write().push((key, result));
+                                }
+                                running.set(false);
+                                links.restart();
+                                if !extended.is_empty() {
+                                    undo::offer(toast, format!("Extended {} links", extended.len()), extended);
+                                }
+                            }
+                        },
+                        if running() {
+                            "Extending..."
+                        } else {
+                            "Extend {ready} links"
+                        }
+                    }
+                }
+                if !report.read().is_empty() {
+                    h2 { class: "text-xl mt-2", "Result" }
+                    for (key , result) in report.read().iter().cloned() {
+                        match result {
+                            Ok(timestamp) => rsx! {
+                                p { class: "border-b border-gray-300 text-green-600",
+                                    "{key}: now expires "
+                                    Expiration { timestamp }
+                                }
+                            },
+                            Err(e) => rsx! {
+                                p { class: "border-b border-gray-300 text-red-500 break-all", "{key}: {e}" }
+                            },
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_792_413_000;
+    const DAY: i64 = 86400;
+
+    #[test]
+    fn window() {
+        let link = |e| utils::test_link("a", Some("https://a.b"), e);
+        let week = 7 * DAY as u32;
+        assert!(in_window(&link(Some(NOW + DAY)), week, false, NOW));
+        assert!(!in_window(&link(Some(NOW + 8 * DAY)), week, false, NOW));
+        assert!(!in_window(&link(Some(NOW - DAY)), week, false, NOW));
+        assert!(in_window(&link(Some(NOW - DAY)), week, true, NOW));
+        assert!(!in_window(&link(None), week, true, NOW));
+    }
+
+    #[test]
+    fn extending() {
+        assert_eq!(Extension::By(60).apply(NOW + 10, NOW), NOW + 70);
+        assert_eq!(Extension::By(60).apply(NOW - DAY, NOW), NOW + 60);
+        assert_eq!(Extension::To(NOW + DAY).apply(NOW + 10, NOW), NOW + DAY);
+    }
+
+    #[test]
+    fn planning() {
+        let link = utils::test_link("a", Some("https://a.b"), Some(NOW + DAY));
+        let body = plan_update(&link, Extension::By(DAY as u32), NOW).unwrap();
+        assert_eq!(body.expiration, Some(NOW + 2 * DAY));
+        assert_eq!(body.url.as_ref(), "https://a.b");
+        assert!(plan_update(&link, Extension::To(NOW - 1), NOW).is_err());
+        assert!(plan_update(
+            &utils::test_link("a", Some("https://a.b"), None),
+            Extension::By(60),
+            NOW
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,601 @@
+use dioxus::prelude::*;
+
+use crate::{
+    destination, duration,
+    settings::use_settings,
+    time::{self, Expiration},
+    utils::{self, CreateRequestBody, Link, UpdateRequestBody},
+};
+
+// Cloudflare KV rejects expirations less than 60 seconds into the future.
+pub const MIN_EXPIRATION_TTL: u32 = 60;
+
+pub const MAX_KEY_LENGTH: usize = 64;
+
+// Paths the backend serves itself, which a short key would shadow.
+const RESERVED_KEYS: [&str; 1] = ["api"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkForm {
+    pub url: String,
+    pub key: String,
+    pub length: String,
+    pub number: bool,
+    pub capital: bool,
+    pub lowercase: bool,
+    pub expiration: String,
+    pub time_zone: String,
+    pub expiration_ttl: String,
+    pub never_expires: bool,
+    // The expiration the link already has, kept to the second while the
+    // minute-precision `expiration` field is left untouched.
+    pub current_expiration: Option<i64>,
+}
+
+impl Default for LinkForm {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            key: String::new(),
+            length: String::from("6"),
+            number: true,
+            capital: true,
+            lowercase: true,
+            expiration: String::new(),
+            time_zone: time::UTC.into(),
+            expiration_ttl: String::new(),
+            never_expires: false,
+            current_expiration: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FormErrors {
+    pub url: Option<Box<str>>,
+    pub key: Option<Box<str>>,
+    pub length: Option<Box<str>>,
+    pub charset: Option<Box<str>>,
+    pub expiration: Option<Box<str>>,
+    pub expiration_ttl: Option<Box<str>>,
+}
+
+impl FormErrors {
+    pub fn is_empty(&self) -> bool {
+        self.url.is_none()
+            && self.key.is_none()
+            && self.length.is_none()
+            && self.charset.is_none()
+            && self.expiration.is_none()
+            && self.expiration_ttl.is_none()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    Url {
+        from: Option<Box<str>>,
+        to: Box<str>,
+    },
+    Expiration {
+        from: Option<i64>,
+        to: Option<i64>,
+    },
+}
+
+impl LinkForm {
+    /// A create form for another link to the same destination. The expiration
+    /// is only carried over while it can still be entered.
+    pub fn duplicate(link: &Link, time_zone: String, now: i64) -> Self {
+        let expiration = link
+            .expiration
+            .filter(|e| *e >= now + i64::from(MIN_EXPIRATION_TTL))
+            .map(|e| time::to_datetime_local(e, time::offset(&time_zone, e)))
+            .unwrap_or_default();
+        Self {
+            url: link.url.as_deref().unwrap_or_default().into(),
+            expiration,
+            time_zone,
+            ..Default::default()
+        }
+    }
+
+    pub fn for_link(link: &Link, time_zone: String) -> Self {
+        let expiration = link
+            .expiration
+            .map(|e| time::to_datetime_local(e, time::offset(&time_zone, e)))
+            .unwrap_or_default();
+        Self {
+            // A broken link starts empty rather than with a placeholder that
+            // could be saved as its destination.
+            url: link.url.as_deref().unwrap_or_default().into(),
+            expiration,
+            time_zone,
+            never_expires: link.expiration.is_none(),
+            current_expiration: link.expiration,
+            ..Default::default()
+        }
+    }
+
+    pub fn create_body(&self, now: i64) -> Result<CreateRequestBody, FormErrors> {
+        let mut errors = FormErrors::default();
+        let url = parse_url(&self.url).map_err(|e| errors.url = Some(e)).ok();
+        let short = parse_key(&self.key).map_err(|e| errors.key = Some(e)).ok();
+        // Length and character sets only shape randomly generated keys.
+        let random = !matches!(short, Some(Some(_)));
+        let length = if random {
+            parse_length(&self.length)
+                .map_err(|e| errors.length = Some(e))
+                .ok()
+        } else {
+            Some(None)
+        };
+        if random && !self.number && !self.capital && !self.lowercase {
+            errors.charset = Some("Enable at least one character set".into());
+        }
+        let expiry = self.parse_expiry(now, &mut errors);
+        match (url, short, length, expiry) {
+            (Some(url), Some(short), Some(length), Some((expiration, expiration_ttl)))
+                if errors.is_empty() =>
+            {
+                Ok(CreateRequestBody {
+                    url,
+                    short,
+                    length,
+                    number: random.then_some(self.number),
+                    capital: random.then_some(self.capital),
+                    lowercase: random.then_some(self.lowercase),
+                    expiration,
+                    expiration_ttl,
+                })
+            }
+            _ => Err(errors),
+        }
+    }
+
+    pub fn update_body(&self, short: &str, now: i64) -> Result<UpdateRequestBody, FormErrors> {
+        let mut errors = FormErrors::default();
+        let url = parse_url(&self.url).map_err(|e| errors.url = Some(e)).ok();
+        let expiry = self.parse_expiry(now, &mut errors);
+        match (url, expiry) {
+            (Some(url), Some((expiration, expiration_ttl))) => Ok(UpdateRequestBody {
+                short: short.into(),
+                url,
+                expiration,
+                expiration_ttl,
+            }),
+            _ => Err(errors),
+        }
+    }
+
+    pub fn create_errors(&self, now: i64) -> FormErrors {
+        self.create_body(now).err().unwrap_or_default()
+    }
+
+    pub fn update_errors(&self, now: i64) -> FormErrors {
+        self.update_body("", now).err().unwrap_or_default()
+    }
+
+    /// The earliest expiration the backend accepts, as a `datetime-local`
+    /// value in the selected time zone.
+    pub fn min_expiration(&self, now: i64) -> String {
+        let min = now + i64::from(MIN_EXPIRATION_TTL);
+        time::to_datetime_local(min, time::offset(&self.time_zone, min))
+    }
+
+    fn parse_expiry(
+        &self,
+        now: i64,
+        errors: &mut FormErrors,
+    ) -> Option<(Option<i64>, Option<u32>)> {
+        if self.never_expires {
+            return Some((None, None));
+        }
+        if let Some(current) = self.current_expiration {
+            let unchanged =
+                time::to_datetime_local(current, time::offset(&self.time_zone, current));
+            if self.expiration == unchanged && self.expiration_ttl.trim().is_empty() {
+                // Sending it back as is would be refused by the backend.
+                if current < now + i64::from(MIN_EXPIRATION_TTL) {
+                    errors.expiration = Some(
+                        "The link has expired, pick a new expiration or make it permanent".into(),
+                    );
+                    return None;
+                }
+                return Some((Some(current), None));
+            }
+        }
+        let expiration = parse_expiration(&self.expiration, &self.time_zone, now)
+            .map_err(|e| errors.expiration = Some(e))
+            .ok();
+        let expiration_ttl = parse_expiration_ttl(&self.expiration_ttl)
+            .map_err(|e| errors.expiration_ttl = Some(e))
+            .ok();
+        match (expiration?, expiration_ttl?) {
+            (Some(_), Some(_)) => {
+                errors.expiration_ttl = Some("Set either an expiration or a TTL, not both".into());
+                None
+            }
+            expiry => Some(expiry),
+        }
+    }
+}
+
+/// What submitting `body` would change about `link`.
+pub fn update_changes(link: &Link, body: &UpdateRequestBody, now: i64) -> Vec<Change> {
+    let mut changes = Vec::new();
+    if link.url.as_ref() != Some(&body.url) {
+        changes.push(Change::Url {
+            from: link.url.clone(),
+            to: body.url.clone(),
+        });
+    }
+    let expiration = body
+        .expiration_ttl
+        .map(|ttl| now + i64::from(ttl))
+        .or(body.expiration);
+    if link.expiration != expiration {
+        changes.push(Change::Expiration {
+            from: link.expiration,
+            to: expiration,
+        });
+    }
+    changes
+}
+
+pub fn parse_url(url: &str) -> Result<Box<str>, Box<str>> {
+    destination::normalize(url)
+}
+
+pub fn parse_key(key: &str) -> Result<Option<Box<str>>, Box<str>> {
+    let key = key.trim();
+    if key.is_empty() {
+        return Ok(None);
+    }
+    if key.len() > MAX_KEY_LENGTH {
+        return Err(format!("Key must be at most {} characters", MAX_KEY_LENGTH).into());
+    }
+    if let Some(c) = key
+        .chars()
+        .find(|c| !c.is_ascii_alphanumeric() && *c != '-' && *c != '_')
+    {
+        return Err(format!("'{}' is not allowed, use letters, digits, - and _", c).into());
+    }
+    if RESERVED_KEYS.contains(&key) {
+        return Err(format!("'{}' is reserved by the backend", key).into());
+    }
+    Ok(Some(key.into()))
+}
+
+pub fn key_taken(key: &str, links: &[Link]) -> bool {
+    let key = key.trim();
+    links.iter().any(|l| l.short.key.as_ref() == key)
+}
+
+pub fn parse_length(length: &str) -> Result<Option<u16>, Box<str>> {
+    let length = length.trim();
+    if length.is_empty() {
+        return Ok(None);
+    }
+    match length.parse::<u16>() {
+        Ok(0) => Err("Length must be at least 1".into()),
+        Ok(l) => Ok(Some(l)),
+        Err(e) => Err(format!("Invalid length, {}", e).into()),
+    }
+}
+
+pub fn parse_expiration(
+    expiration: &str,
+    time_zone: &str,
+    now: i64,
+) -> Result<Option<i64>, Box<str>> {
+    let expiration = expiration.trim();
+    if expiration.is_empty() {
+        return Ok(None);
+    }
+    let local = time::parse_datetime_local(expiration).ok_or("Invalid expiration date")?;
+    let timestamp = time::to_timestamp(local, |t| time::offset(time_zone, t));
+    if timestamp < now + i64::from(MIN_EXPIRATION_TTL) {
+        return Err(format!(
+            "Expiration must be at least {} seconds in the future",
+            MIN_EXPIRATION_TTL
+        )
+        .into());
+    }
+    Ok(Some(timestamp))
+}
+
+pub fn parse_expiration_ttl(expiration_ttl: &str) -> Result<Option<u32>, Box<str>> {
+    let expiration_ttl = expiration_ttl.trim();
+    if expiration_ttl.is_empty() {
+        return Ok(None);
+    }
+    match duration::parse_duration(expiration_ttl) {
+        Ok(ttl) if ttl < MIN_EXPIRATION_TTL => Err(format!(
+            "ExpirationTTL must be at least {} seconds",
+            MIN_EXPIRATION_TTL
+        )
+        .into()),
+        Ok(ttl) => Ok(Some(ttl)),
+        Err(e) => Err(format!("Invalid expirationTTL, {}", e).into()),
+    }
+}
+
+/// A form handed to the `Create` page by another page.
+#[derive(Clone, Copy)]
+pub struct Draft(Signal<Option<LinkForm>>);
+
+impl Draft {
+    pub fn set(&mut self, form: LinkForm) {
+        self.0.set(Some(form));
+    }
+
+    pub fn take(&mut self) -> Option<LinkForm> {
+        self.0.take()
+    }
+}
+
+pub fn use_draft_provider() -> Draft {
+    use_context_provider(|| Draft(Signal::new(None)))
+}
+
+pub fn use_draft() -> Draft {
+    use_context()
+}
+
+#[component]
+pub fn FieldError(error: Option<Box<str>>) -> Element {
+    rsx! {
+        if let Some(error) = error {
+            p { class: "text-sm text-red-500", "{error}" }
+        }
+    }
+}
+
+#[component]
+pub fn TtlPresets(onselect: EventHandler<String>) -> Element {
+    let settings = use_settings();
+    rsx! {
+        div { class: "flex flex-wrap gap-1 mt-1",
+            for preset in settings.read().ttl_presets.iter().cloned() {
+                button {
+                    r#type: "button",
+                    class: "border border-gray-300 hover:bg-gray-200 px-1 text-sm",
+                    cursor: "pointer",
+                    onclick: move |_| onselect.call(preset.to_string()),
+                    "{preset}"
+                }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn TtlExpiry(ttl: String) -> Element {
+    let Ok(Some(ttl)) = parse_expiration_ttl(&ttl) else {
+        return rsx! {};
+    };
+    rsx! {
+        p { class: "text-sm text-gray-500",
+            "Expires in {duration::format_duration(ttl)}, at "
+            Expiration { timestamp: utils::now() + i64::from(ttl) }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_749_999_960;
+
+    fn at(timestamp: i64) -> String {
+        time::to_datetime_local(timestamp, 0)
+    }
+
+    fn form() -> LinkForm {
+        LinkForm {
+            url: "https://example.com".into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn url() {
+        assert_eq!(
+            parse_url(" https://a.b/c ").unwrap().as_ref(),
+            "https://a.b/c"
+        );
+        assert!(parse_url("").is_err());
+        assert_eq!(
+            parse_url("example.com").unwrap().as_ref(),
+            "https://example.com"
+        );
+        assert!(parse_url("https://").is_err());
+        assert!(parse_url("https://a b").is_err());
+    }
+
+    #[test]
+    fn key() {
+        assert_eq!(parse_key(" "), Ok(None));
+        assert_eq!(parse_key("launch-2026"), Ok(Some("launch-2026".into())));
+        assert_eq!(parse_key("a_B_1"), Ok(Some("a_B_1".into())));
+        assert!(parse_key("launch 2026").is_err());
+        assert!(parse_key("a/b").is_err());
+        assert!(parse_key("ключ").is_err());
+        assert!(parse_key("api").is_err());
+        assert!(parse_key(&"a".repeat(MAX_KEY_LENGTH + 1)).is_err());
+        assert!(key_taken("abc", &[link(None)]));
+        assert!(!key_taken("abcd", &[link(None)]));
+    }
+
+    #[test]
+    fn custom_key() {
+        let body = LinkForm {
+            key: "launch-2026".into(),
+            length: "invalid".into(),
+            number: false,
+            capital: false,
+            lowercase: false,
+            ..form()
+        }
+        .create_body(NOW)
+        .unwrap();
+        assert_eq!(body.short.as_deref(), Some("launch-2026"));
+        assert_eq!((body.length, body.number), (None, None));
+    }
+
+    #[test]
+    fn length() {
+        assert_eq!(parse_length(""), Ok(None));
+        assert_eq!(parse_length("8"), Ok(Some(8)));
+        assert!(parse_length("0").is_err());
+        assert!(parse_length("-1").is_err());
+        assert!(parse_length("70000").is_err());
+    }
+
+    #[test]
+    fn expiration() {
+        assert_eq!(parse_expiration("", time::UTC, NOW), Ok(None));
+        assert_eq!(
+            parse_expiration(&at(NOW + 3600), time::UTC, NOW),
+            Ok(Some(NOW + 3600))
+        );
+        assert!(parse_expiration(&at(NOW), time::UTC, NOW).is_err());
+        assert!(parse_expiration(&at(NOW - 3600), time::UTC, NOW).is_err());
+        assert!(parse_expiration("2025-01-01 00:00:00 +0000", time::UTC, NOW).is_err());
+    }
+
+    #[test]
+    fn expiration_ttl() {
+        assert_eq!(parse_expiration_ttl(""), Ok(None));
+        assert_eq!(parse_expiration_ttl("60"), Ok(Some(60)));
+        assert_eq!(parse_expiration_ttl("1h"), Ok(Some(3600)));
+        assert!(parse_expiration_ttl("59").is_err());
+        assert!(parse_expiration_ttl("59s").is_err());
+        assert!(parse_expiration_ttl("1 hour").is_err());
+    }
+
+    #[test]
+    fn create_body() {
+        let body = form().create_body(NOW).unwrap();
+        assert_eq!(body.url.as_ref(), "https://example.com");
+        assert_eq!(body.length, Some(6));
+        assert_eq!(body.number, Some(true));
+        assert_eq!(body.expiration, None);
+
+        let errors = LinkForm {
+            number: false,
+            capital: false,
+            lowercase: false,
+            ..form()
+        }
+        .create_errors(NOW);
+        assert!(errors.charset.is_some());
+
+        let errors = LinkForm {
+            expiration: at(NOW + 3600),
+            expiration_ttl: "3600".into(),
+            ..form()
+        }
+        .create_errors(NOW);
+        assert!(errors.expiration.is_none());
+        assert!(errors.expiration_ttl.is_some());
+    }
+
+    fn link(expiration: Option<i64>) -> Link {
+        utils::test_link("abc", Some("https://example.com"), expiration)
+    }
+
+    #[test]
+    fn for_link() {
+        // Not on a minute boundary, so it cannot be entered again as is.
+        let current = NOW + 90;
+        let form = LinkForm::for_link(&link(Some(current)), time::UTC.into());
+        let body = form.update_body("abc", NOW).unwrap();
+        assert_eq!(body.url.as_ref(), "https://example.com");
+        assert_eq!(body.expiration, Some(current));
+        assert!(update_changes(&link(Some(current)), &body, NOW).is_empty());
+
+        let form = LinkForm {
+            never_expires: true,
+            ..form
+        };
+        let body = form.update_body("abc", NOW).unwrap();
+        assert_eq!(body.expiration, None);
+        assert_eq!(
+            update_changes(&link(Some(current)), &body, NOW),
+            [Change::Expiration {
+                from: Some(current),
+                to: None
+            }]
+        );
+
+        let form = LinkForm::for_link(&link(None), time::UTC.into());
+        assert!(form.never_expires);
+        assert_eq!(form.expiration, "");
+
+        // Even a URL-only change needs a new expiration once the old one passed.
+        let expired = LinkForm::for_link(&link(Some(NOW - 30)), time::UTC.into());
+        assert!(expired.update_errors(NOW).expiration.is_some());
+        let soon = LinkForm::for_link(&link(Some(NOW + 30)), time::UTC.into());
+        assert!(soon.update_errors(NOW).expiration.is_some());
+        let repaired = LinkForm {
+            never_expires: true,
+            ..expired
+        };
+        assert_eq!(repaired.update_body("abc", NOW).unwrap().expiration, None);
+
+        let broken = utils::test_link("abc", None, None);
+        let form = LinkForm::for_link(&broken, time::UTC.into());
+        assert_eq!(form.url, "");
+        assert!(form.update_errors(NOW).url.is_some());
+    }
+
+    #[test]
+    fn duplicate() {
+        let form = LinkForm::duplicate(&link(Some(NOW + 3600)), time::UTC.into(), NOW);
+        assert_eq!(form.url, "https://example.com");
+        assert_eq!(form.key, "");
+        assert_eq!(form.create_body(NOW).unwrap().expiration, Some(NOW + 3600));
+        let form = LinkForm::duplicate(&link(Some(NOW)), time::UTC.into(), NOW);
+        assert_eq!(form.expiration, "");
+    }
+
+    #[test]
+    fn changes() {
+        let form = LinkForm {
+            url: "https://example.org".into(),
+            expiration: String::new(),
+            expiration_ttl: "1h".into(),
+            ..LinkForm::for_link(&link(Some(NOW + 600)), time::UTC.into())
+        };
+        let body = form.update_body("abc", NOW).unwrap();
+        assert_eq!(
+            update_changes(&link(Some(NOW + 600)), &body, NOW),
+            [
+                Change::Url {
+                    from: Some("https://example.com".into()),
+                    to: "https://example.org".into()
+                },
+                Change::Expiration {
+                    from: Some(NOW + 600),
+                    to: Some(NOW + 3600)
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn update_body() {
+        let form = LinkForm {
+            length: "not checked on update".into(),
+            expiration_ttl: "120".into(),
+            ..form()
+        };
+        let body = form.update_body("abc", NOW).unwrap();
+        assert_eq!(body.short.as_ref(), "abc");
+        assert_eq!(body.expiration_ttl, Some(120));
+        assert!(LinkForm::default().update_errors(NOW).url.is_some());
+    }
+}
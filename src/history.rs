@@ -0,0 +1,453 @@
+use std::fmt;
+
+use chrono::DateTime;
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::Result,
+    time, trash,
+    utils::{self, CreateRequestBody, Link, Short, UpdateRequestBody},
+    Route,
+};
+
+// Entries are only ever appended, none are dropped or rewritten.
+const STORAGE_KEY: &str = "history";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    Create,
+    Update,
+    Delete,
+}
+
+impl Action {
+    pub const ALL: [Action; 3] = [Action::Create, Action::Update, Action::Delete];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Create => "create",
+            Action::Update => "update",
+            Action::Delete => "delete",
+        }
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// What a key pointed to before or after a change.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct State {
+    pub url: Option<Box<str>>,
+    pub expiration: Option<i64>,
+}
+
+impl From<&Link> for State {
+    fn from(link: &Link) -> Self {
+        Self {
+            url: link.url.clone(),
+            expiration: link.expiration,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    pub at: i64,
+    /// The backend the change was sent to.
+    pub profile: String,
+    pub action: Action,
+    pub key: Box<str>,
+    pub before: Option<State>,
+    pub after: Option<State>,
+    /// `None` when the backend accepted the change.
+    pub error: Option<String>,
+}
+
+pub fn load() -> Vec<Entry> {
+    utils::load(STORAGE_KEY).ok().flatten().unwrap_or_default()
+}
+
+/// Appends to the log. Failing to log never fails the change itself, but
+/// the user is told about it.
+fn append(entry: Entry) {
+    let mut entries = load();
+    let key = entry.key.clone();
+    entries.push(entry);
+    if let Err(e) = utils::save(STORAGE_KEY, &entries) {
+        utils::alert(&format!(
+            "The change to {} was made but could not be written to the history.\n\nError: {}",
+            key, e
+        ));
+    }
+}
+
+fn entry(action: Action, key: &str, before: Option<State>, after: Option<State>) -> Entry {
+    Entry {
+        at: utils::now(),
+        profile: utils::backend_host().unwrap_or_default(),
+        action,
+        key: key.into(),
+        before,
+        after,
+        error: None,
+    }
+}
+
+fn with_result<T>(entry: Entry, result: &Result<T>) -> Entry {
+    Entry {
+        error: result.as_ref().err().map(|e| e.to_string()),
+        ..entry
+    }
+}
+
+pub async fn create_link(body: CreateRequestBody) -> Result<Short> {
+    let after = State {
+        url: Some(body.url.clone()),
+        expiration: body
+            .expiration
+            .or(body.expiration_ttl.map(|ttl| utils::now() + i64::from(ttl))),
+    };
+    let requested = body.short.clone().unwrap_or_default();
+    let result = utils::create_link(body).await;
+    let key = result.as_ref().map(|s| s.key.clone()).unwrap_or(requested);
+    append(with_result(
+        entry(Action::Create, &key, None, Some(after)),
+        &result,
+    ));
+    result
+}
+
+pub async fn update_link(before: &Link, body: UpdateRequestBody) -> Result<()> {
+    let after = State {
+        url: Some(body.url.clone()),
+        expiration: body
+            .expiration_ttl
+            .map(|ttl| utils::now() + i64::from(ttl))
+            .or(body.expiration),
+    };
+    let key = body.short.clone();
+    let result = utils::update_link(body).await;
+    append(with_result(
+        entry(Action::Update, &key, Some(before.into()), Some(after)),
+        &result,
+    ));
+    result
+}
+
+/// Why a link is deleted, which decides whether it goes to the trash.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Removal {
+    /// The user deleted it and may want it back.
+    Deleted,
+    /// It lives on under a new key, restoring the old one would duplicate it.
+    Renamed,
+    /// The user never asked for it, e.g. the backend created it under another key.
+    Stray,
+    /// Cleaned up because it expired or lost its destination, so it cannot be created again.
+    Purged,
+}
+
+impl Removal {
+    pub fn keeps_in_trash(self) -> bool {
+        self == Removal::Deleted
+    }
+}
+
+/// Deletes a link and logs it, only a `Removal::Deleted` link lands in the trash.
+pub async fn delete_link(before: &Link, removal: Removal) -> Result<()> {
+    let result = utils::delete_link(&before.short.key).await;
+    append(with_result(
+        entry(Action::Delete, &before.short.key, Some(before.into()), None),
+        &result,
+    ));
+    if result.is_ok() && removal.keeps_in_trash() {
+        if let Err(e) = trash::add(before) {
+            utils::alert(&format!(
+                "{} was deleted but could not be kept in the trash, so it cannot be restored.\n\nError: {}",
+                before.short.key, e
+            ));
+        }
+    }
+    result
+}
+
+fn format_time(timestamp: i64) -> String {
+    DateTime::from_timestamp(timestamp, 0)
+        .map(|d| d.format("%Y-%m-%dT%H:%M:%SZ").to_string())
+        .unwrap_or_default()
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.into()
+    }
+}
+
+pub fn to_csv(entries: &[Entry]) -> String {
+    let mut csv = String::from(
+        "time,profile,action,key,before_url,before_expiration,after_url,after_expiration,result\r\n",
+    );
+    let state = |state: &Option<State>| {
+        let url = state
+            .as_ref()
+            .and_then(|s| s.url.as_deref())
+            .unwrap_or_default();
+        let expiration = state
+            .as_ref()
+            .and_then(|s| s.expiration)
+            .map(format_time)
+            .unwrap_or_default();
+        [url.to_string(), expiration]
+    };
+    for entry in entries {
+        let [before_url, before_expiration] = state(&entry.before);
+        let [after_url, after_expiration] = state(&entry.after);
+        let fields = [
+            format_time(entry.at),
+            entry.profile.clone(),
+            entry.action.to_string(),
+            entry.key.to_string(),
+            before_url,
+            before_expiration,
+            after_url,
+            after_expiration,
+            entry.error.clone().unwrap_or_else(|| "ok".into()),
+        ];
+        let fields: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        csv.push_str(&fields.join(","));
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+/// Case-insensitive search in the key and both URLs.
+pub fn matches(entry: &Entry, action: &str, profile: &str, search: &str) -> bool {
+    let search = search.trim().to_lowercase();
+    let url = |state: &Option<State>| {
+        state
+            .as_ref()
+            .and_then(|s| s.url.as_deref())
+            .is_some_and(|u| u.to_lowercase().contains(&search))
+    };
+    (action.is_empty() || entry.action.name() == action)
+        && (profile.is_empty() || entry.profile == profile)
+        && (search.is_empty()
+            || entry.key.to_lowercase().contains(&search)
+            || url(&entry.before)
+            || url(&entry.after))
+}
+
+/// The entries `matches` keeps, newest first.
+fn filter(entries: &[Entry], action: &str, profile: &str, search: &str) -> Vec<Entry> {
+    entries
+        .iter()
+        .rev()
+        .filter(|e| matches(e, action, profile, search))
+        .cloned()
+        .collect()
+}
+
+#[component]
+pub fn History() -> Element {
+    let entries = use_signal(load);
+    let mut action = use_signal(String::new);
+    let mut profile = use_signal(String::new);
+    let mut search = use_signal(String::new);
+    let zone = time::browser_time_zone();
+    let mut profiles: Vec<String> = entries.read().iter().map(|e| e.profile.clone()).collect();
+    profiles.sort();
+    profiles.dedup();
+    let shown = filter(
+        &entries.read(),
+        &action.read(),
+        &profile.read(),
+        &search.read(),
+    );
+    // Exports are built from the same filters, only once they are asked for.
+    let export = move || {
+        filter(
+            &entries.read(),
+            &action.read(),
+            &profile.read(),
+            &search.read(),
+        )
+    };
+    rsx! {
+        div {
+            div { class: "flex flex-col",
+                h1 { class: "text-3xl mx-auto", "History" }
+                p { class: "mx-auto text-gray-500", "Changes made from this browser" }
+            }
+            div { class: "flex flex-col w-9/12 sm:w-2/3 mx-auto mt-5",
+                div { class: "flex flex-wrap gap-2 items-center",
+                    select {
+                        class: "border border-gray-300 px-2",
+                        cursor: "pointer",
+                        onchange: move |e| action.set(e.value()),
+                        option { value: "", "Any action" }
+                        for a in Action::ALL {
+                            option { value: a.name(), selected: *action.read() == a.name(), "{a}" }
+                        }
+                    }
+                    select {
+                        class: "border border-gray-300 px-2",
+                        cursor: "pointer",
+                        onchange: move |e| profile.set(e.value()),
+                        option { value: "", "Any backend" }
+                        for p in profiles {
+                            option { value: "{p}", selected: *profile.read() == p, "{p}" }
+                        }
+                    }
+                    input {
+                        class: "border border-gray-300 px-2",
+                        r#type: "search",
+                        placeholder: "Search keys and URLs",
+                        value: "{search}",
+                        oninput: move |e| search.set(e.value()),
+                    }
+                    button {
+                        r#type: "button",
+                        class: "border border-gray-300 hover:bg-gray-200 px-2",
+                        cursor: "pointer",
+                        onclick: move |_| {
+                            utils::export(
+                                "linkrusk-history.json",
+                                "application/json",
+                                &serde_json::to_string_pretty(&export()).unwrap_or_default(),
+                            )
+                        },
+                        "Export JSON"
+                    }
+                    button {
+                        r#type: "button",
+                        class: "border border-gray-300 hover:bg-gray-200 px-2",
+                        cursor: "pointer",
+                        onclick: move |_| utils::export("linkrusk-history.csv", "text/csv", &to_csv(&export())),
+                        "Export CSV"
+                    }
+                }
+                p { class: "text-sm text-gray-500 mt-1",
+                    "{shown.len()} of {entries.read().len()} changes, all are kept"
+                }
+                for entry in shown {
+                    div { class: "border-b border-gray-300 py-1",
+                        p {
+                            span { class: "text-sm text-gray-500",
+                                "{time::format_local(entry.at, time::offset(&zone, entry.at))} · {entry.profile} · "
+                            }
+                            "{entry.action} "
+                            Link {
+                                class: "underline",
+                                to: Route::LinkItem {
+                                    link: entry.key.to_string(),
+                                },
+                                "{entry.key}"
+                            }
+                            match &entry.error {
+                                None => rsx! {
+                                    span { class: "text-green-600", " ok" }
+                                },
+                                Some(e) => rsx! {
+                                    span { class: "text-red-500 break-all", " failed: {e}" }
+                                },
+                            }
+                        }
+                        if let Some(before) = entry.before {
+                            StateLine { label: "Before", state: before }
+                        }
+                        if let Some(after) = entry.after {
+                            StateLine { label: "After", state: after }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn StateLine(label: &'static str, state: State) -> Element {
+    rsx! {
+        p { class: "text-sm break-all",
+            "{label}: {state.url.as_deref().unwrap_or(\"(no URL)\")}, expires "
+            match state.expiration {
+                Some(timestamp) => rsx! {
+                    time::Expiration { timestamp }
+                },
+                None => rsx! { "never" },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_792_413_000;
+
+    fn update(key: &str, profile: &str) -> Entry {
+        Entry {
+            at: NOW,
+            profile: profile.into(),
+            action: Action::Update,
+            key: key.into(),
+            before: Some(State {
+                url: Some("https://a.b/old".into()),
+                expiration: None,
+            }),
+            after: Some(State {
+                url: Some("https://a.b/new,\"x\"".into()),
+                expiration: Some(NOW + 60),
+            }),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn csv() {
+        let failed = Entry {
+            action: Action::Delete,
+            after: None,
+            error: Some("Not found".into()),
+            ..update("b", "s.example")
+        };
+        let csv = to_csv(&[update("a", "s.example"), failed]);
+        let lines: Vec<&str> = csv.split("\r\n").collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(
+            lines[1],
+            "2026-10-19T12:30:00Z,s.example,update,a,https://a.b/old,,\"https://a.b/new,\"\"x\"\"\",2026-10-19T12:31:00Z,ok"
+        );
+        assert_eq!(
+            lines[2],
+            "2026-10-19T12:30:00Z,s.example,delete,b,https://a.b/old,,,,Not found"
+        );
+    }
+
+    #[test]
+    fn filtering() {
+        let entry = update("Promo", "s.example");
+        assert!(matches(&entry, "", "", ""));
+        assert!(matches(&entry, "update", "s.example", "promo"));
+        assert!(matches(&entry, "", "", "OLD"));
+        assert!(!matches(&entry, "delete", "", ""));
+        assert!(!matches(&entry, "", "t.example", ""));
+        assert!(!matches(&entry, "", "", "fall"));
+    }
+
+    #[test]
+    fn serialized() {
+        let json = serde_json::to_value(update("a", "s.example")).unwrap();
+        assert_eq!(json["action"], "update");
+        assert_eq!(json["before"]["expiration"], serde_json::Value::Null);
+    }
+}
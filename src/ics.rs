@@ -0,0 +1,184 @@
+use chrono::DateTime;
+use dioxus::prelude::*;
+
+use crate::utils::{self, Link};
+
+// RFC 5545 limits content lines to 75 octets, excluding the line break.
+const LINE_LIMIT: usize = 75;
+
+fn format_time(timestamp: i64) -> String {
+    DateTime::from_timestamp(timestamp, 0)
+        .map(|d| d.format("%Y%m%dT%H%M%SZ").to_string())
+        .unwrap_or_default()
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | ';' | ',' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Appends a content line, folding it without splitting a character.
+fn push_line(ics: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        // Continuation lines start with a space, which counts towards the limit.
+        if width + c.len_utf8() > LINE_LIMIT {
+            ics.push_str("\r\n ");
+            width = 1;
+        }
+        ics.push(c);
+        width += c.len_utf8();
+    }
+    ics.push_str("\r\n");
+}
+
+/// One event per link with an expiration. The UID only depends on the key and
+/// the backend, so importing a newer export updates the existing events.
+pub fn calendar(links: &[Link], host: &str, alarm_days: Option<u32>, now: i64) -> String {
+    let mut ics = String::new();
+    for line in [
+        "BEGIN:VCALENDAR",
+        "VERSION:2.0",
+        "PRODID:-//linkrusk//Expirations//EN",
+        "CALSCALE:GREGORIAN",
+        "METHOD:PUBLISH",
+    ] {
+        push_line(&mut ics, line);
+    }
+    for link in links {
+        let Some(expiration) = link.expiration else {
+            continue;
+        };
+        let key = &link.short.key;
+        let description = format!(
+            "Key: {}\nShort URL: {}\nDestination: {}",
+            key,
+            link.short.full,
+            link.url.as_deref().unwrap_or("(missing)")
+        );
+        push_line(&mut ics, "BEGIN:VEVENT");
+        push_line(&mut ics, &format!("UID:{}@{}", escape(key), escape(host)));
+        push_line(&mut ics, &format!("DTSTAMP:{}", format_time(now)));
+        // The event follows its expiration, so both revision fields do too and
+        // importing an unchanged link again leaves its event alone.
+        push_line(&mut ics, &format!("SEQUENCE:{}", expiration.max(0) / 60));
+        push_line(
+            &mut ics,
+            &format!("LAST-MODIFIED:{}", format_time(expiration)),
+        );
+        push_line(&mut ics, &format!("DTSTART:{}", format_time(expiration)));
+        push_line(&mut ics, &format!("DTEND:{}", format_time(expiration)));
+        push_line(&mut ics, &format!("SUMMARY:{} expires", escape(key)));
+        push_line(&mut ics, &format!("DESCRIPTION:{}", escape(&description)));
+        push_line(&mut ics, &format!("URL:{}", link.short.full));
+        if let Some(days) = alarm_days {
+            push_line(&mut ics, "BEGIN:VALARM");
+            push_line(&mut ics, "ACTION:DISPLAY");
+            push_line(
+                &mut ics,
+                &format!("DESCRIPTION:{} expires in {} days", escape(key), days),
+            );
+            push_line(&mut ics, &format!("TRIGGER:-P{}D", days));
+            push_line(&mut ics, "END:VALARM");
+        }
+        push_line(&mut ics, "END:VEVENT");
+    }
+    push_line(&mut ics, "END:VCALENDAR");
+    ics
+}
+
+#[component]
+pub fn IcsExport(links: Vec<Link>) -> Element {
+    let mut alarm = use_signal(String::new);
+    let alarm_days = alarm.read().trim().parse::<u32>().ok();
+    let host = utils::backend_host().unwrap_or_default();
+    let count = links.iter().filter(|l| l.expiration.is_some()).count();
+    rsx! {
+        div { class: "flex flex-wrap gap-1 items-center mt-2",
+            button {
+                r#type: "button",
+                class: "border border-gray-300 hover:bg-gray-200 px-2",
+                cursor: "pointer",
+                onclick: move |_| {
+                    utils::export(
+                        "linkrusk-expirations.ics",
+                        "text/calendar",
+                        &calendar(&links, &host, alarm_days, utils::now()),
+                    )
+                },
+                "Export expirations (.ics)"
+            }
+            span { class: "text-sm text-gray-500", "{count} events, remind" }
+            input {
+                class: "border border-gray-300 px-1 w-20",
+                r#type: "number",
+                min: 0,
+                placeholder: "never",
+                value: "{alarm}",
+                oninput: move |e| alarm.set(e.value()),
+            }
+            span { class: "text-sm text-gray-500", "days before" }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2026-10-19 12:30 UTC.
+    const NOW: i64 = 1_792_413_000;
+
+    #[test]
+    fn escaping() {
+        assert_eq!(escape("a,b;c\\d\ne"), "a\\,b\\;c\\\\d\\ne");
+    }
+
+    #[test]
+    fn folding() {
+        let mut ics = String::new();
+        push_line(&mut ics, &"x".repeat(80));
+        assert_eq!(ics, format!("{}\r\n {}\r\n", "x".repeat(75), "x".repeat(5)));
+        let mut ics = String::new();
+        push_line(&mut ics, &"ü".repeat(40));
+        for line in ics.split("\r\n") {
+            assert!(line.len() <= LINE_LIMIT);
+        }
+        assert_eq!(ics.replace("\r\n ", ""), format!("{}\r\n", "ü".repeat(40)));
+    }
+
+    #[test]
+    fn events() {
+        let links = [
+            utils::test_link("a", Some("https://a.b/?x=1,2"), Some(NOW + 3600)),
+            utils::test_link("b", Some("https://c.d"), None),
+        ];
+        let ics = calendar(&links, "s.example", Some(3), NOW);
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 1);
+        assert!(ics.contains("\r\nUID:a@s.example\r\n"));
+        assert!(ics.contains("\r\nDTSTART:20261019T133000Z\r\n"));
+        assert!(ics.contains("\r\nDTSTAMP:20261019T123000Z\r\n"));
+        assert!(ics.contains("\r\nSEQUENCE:29873610\r\n"));
+        assert!(ics.contains("\r\nLAST-MODIFIED:20261019T133000Z\r\n"));
+        let later = [utils::test_link("a", Some("https://a.b"), Some(NOW + 7200))];
+        assert!(calendar(&later, "s.example", None, NOW).contains("\r\nSEQUENCE:29873670\r\n"));
+        assert!(ics
+            .replace("\r\n ", "")
+            .contains("\\nDestination: https://a.b/?x=1\\,2\r\n"));
+        assert!(ics.contains("\r\nTRIGGER:-P3D\r\n"));
+        assert!(!calendar(&links, "s.example", None, NOW).contains("VALARM"));
+    }
+}
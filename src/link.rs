@@ -1,27 +1,42 @@
 use std::rc::Rc;
 
-use chrono::DateTime;
 use dioxus::prelude::*;
 
 use crate::{
+    destination::UrlCheck,
+    form::{self, Change, FieldError, LinkForm, TtlExpiry, TtlPresets},
+    history::{self, Removal},
+    meta::MetaEditor,
+    policy::use_policy,
+    qr::QrPanel,
+    query::{self, QueryBuilder},
+    rename::RenameKey,
+    settings::use_settings,
+    share::SharePanel,
+    store::use_links,
+    time::{self, Expiration, TimeZoneSelect},
+    undo::{self, Undo},
     utils::{self, Link},
     Route,
 };
 
 #[component]
 pub fn LinkItem(link: String) -> Element {
-    #[allow(clippy::redundant_closure)]
-    let links = use_resource(|| utils::fetch_links());
+    let links = use_links();
     rsx! {
         match links() {
-            Some(links) => {
+            Some(Err(e)) => return Err(e.into()),
+            Some(Ok(links)) => {
                 let link = links.iter().find(|l| l.short.key.as_ref() == link).cloned();
                 if let Some(link) = link {
-                    link_item_render(link)
+                    // The form is only filled in on mount, so it restarts whenever the
+                    // link changes, e.g. after an update or an undo.
+                    rsx! {
+                        LinkEdit { key: "{link.short.key}-{link.expiration:?}-{link.url:?}", link }
+                    }
                 } else {
                     rsx! {
                         p { class: "mb-2 text-2xl", "Link not found." }
-                        "You might need a refresh if you came from create"
                     }
                 }
             }
@@ -32,74 +47,131 @@ pub fn LinkItem(link: String) -> Element {
     }
 }
 
-fn link_item_render(link: Link) -> Element {
+// A TTL replaces the expiration the form was prefilled with.
+fn set_ttl(mut form: Signal<LinkForm>, ttl: String) {
+    let mut form = form.write();
+    if !ttl.trim().is_empty() {
+        form.expiration.clear();
+    }
+    form.expiration_ttl = ttl;
+}
+
+#[component]
+fn ChangePreview(changes: Vec<Change>) -> Element {
+    rsx! {
+        div { class: "my-2",
+            if changes.is_empty() {
+                p { class: "text-gray-500", "No changes." }
+            } else {
+                p { class: "text-xl", "Will change:" }
+                ul { class: "list-disc pl-5",
+                    for change in changes {
+                        match change {
+                            Change::Url { from, to } => rsx! {
+                                li { class: "break-all",
+                                    "url: {from.as_deref().unwrap_or(\"(none)\")} → {to}"
+                                }
+                            },
+                            Change::Expiration { from, to } => rsx! {
+                                li {
+                                    "expiration: "
+                                    ExpirationOrNever { timestamp: from }
+                                    " → "
+                                    ExpirationOrNever { timestamp: to }
+                                }
+                            },
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn ExpirationOrNever(timestamp: Option<i64>) -> Element {
+    match timestamp {
+        Some(timestamp) => rsx! {
+            Expiration { timestamp }
+        },
+        None => rsx! { "never" },
+    }
+}
+
+#[component]
+fn RepairNotice() -> Element {
+    rsx! {
+        div { class: "flex flex-col w-9/12 sm:w-2/3 mx-auto mt-2 border border-red-500 p-2",
+            p { class: "text-xl text-red-500", "This link is broken" }
+            p { "The backend has no destination URL for this key, so visitors cannot be redirected." }
+            ol { class: "list-decimal pl-5",
+                li { "Enter the new destination in To URL below and press Update, or" }
+                li { "delete the key with Delete This Link if it is no longer needed." }
+            }
+            p { class: "text-sm text-gray-500",
+                "Several broken links can be removed at once from "
+                Link { class: "underline", to: Route::Cleanup, "Purge" }
+                "."
+            }
+        }
+    }
+}
+
+#[component]
+fn LinkEdit(link: Link) -> Element {
     let key: Rc<str> = link.short.key.clone().into();
-    let url = link
-        .url
-        .clone()
-        .unwrap_or_else(|| Box::from("Failed to display"));
-    let expiration = link
-        .expiration
-        .map(|e| DateTime::from_timestamp(e, 0).unwrap().to_string())
-        .unwrap_or_default();
+    let mut links = use_links();
+    let mut draft = form::use_draft();
+    let toast = undo::use_undo();
+    let mut form = use_signal(|| LinkForm::for_link(&link, time::browser_time_zone()));
+    let mut pasted = use_signal(|| false);
+    let settings = use_settings();
+    let policy = use_policy();
+    let mut errors = form.read().update_errors(utils::now());
+    if errors.url.is_none() {
+        errors.url = policy
+            .read()
+            .check_change(link.url.as_deref(), &form.read().url)
+            .err();
+    }
+    let changes = form
+        .read()
+        .update_body(&key, utils::now())
+        .map(|body| form::update_changes(&link, &body, utils::now()));
+    let never_expires = form.read().never_expires;
+    let min_expiration = form.read().min_expiration(utils::now());
     rsx! {
         div { class: "",
             div { class: "flex flex-col",
                 h1 { class: "text-3xl mx-auto", "Key: {key}" }
             }
+            if link.url.is_none() {
+                RepairNotice {}
+            }
             form {
                 class: "mt-2",
                 onsubmit: {
                     let key = key.clone();
-                    move |event: FormEvent| {
-                        let key = key.clone();
+                    let link = link.clone();
+                    move |_| {
+                        let (key, link) = (key.clone(), link.clone());
                         async move {
-                            let values = event.values();
-                            let url = values["url"].as_value().to_string().into_boxed_str();
-                            let expiration = values["expiration"].as_value();
-                            let expiration = if expiration.is_empty() {
-                                None
-                            } else {
-                                match DateTime::parse_from_str(&expiration, "%Y-%m-%d %H:%M:%S %z") {
-                                    Ok(d) => Some(d.timestamp()),
-                                    Err(e) => {
-                                        web_sys::window()
-                                            .unwrap()
-                                            .alert_with_message(
-                                                &format!("Invalid expiration date format, {}", e),
-                                            )
-                                            .unwrap();
-                                        return;
-                                    }
-                                }
+                            let Ok(body) = form.read().update_body(&key, utils::now()) else {
+                                return;
                             };
-                            let expiration_ttl = values["expirationTtl"].as_value();
-                            let expiration_ttl = if expiration_ttl.is_empty() {
-                                None
-                            } else {
-                                match expiration_ttl.parse::<u32>() {
-                                    Ok(e) => Some(e),
-                                    Err(e) => {
-                                        web_sys::window()
-                                            .unwrap()
-                                            .alert_with_message(
-                                                &format!("Invalid expirationTTL, {}", e),
-                                            )
-                                            .unwrap();
-                                        return;
-                                    }
+                            if let Err(e) = policy.read().check_change(link.url.as_deref(), &body.url) {
+                                utils::alert(&e);
+                                return;
+                            }
+                            let revert = Undo::revert(link.clone(), &body, utils::now());
+                            match history::update_link(&link, body).await {
+                                Ok(()) => {
+                                    links.restart();
+                                    undo::offer(toast, format!("Updated {}", key), vec![revert]);
+                                }
+                                Err(e) => {
+                                    utils::alert(&format!("Failed to update the link.\n\nError: {}", e))
                                 }
-                            };
-                            let body = utils::UpdateRequestBody {
-                                short: key.as_ref().into(),
-                                url,
-                                expiration,
-                                expiration_ttl,
-                            };
-                            if utils::update_link(body).await {
-                                let window = web_sys::window().unwrap();
-                                window.alert_with_message("Link updated").unwrap();
-                                window.location().reload().unwrap();
                             }
                         }
                     }
@@ -114,16 +186,50 @@ fn link_item_render(link: Link) -> Element {
                                     rows: 4,
                                     resize: "none",
                                     name: "url",
-                                    value: "{url}",
+                                    value: "{form.read().url}",
+                                    onpaste: move |_| pasted.set(true),
+                                    oninput: move |e| {
+                                        let url = e.value();
+                                        let strip = pasted() && settings.read().strip_tracking;
+                                        pasted.set(false);
+                                        form.write().url = if strip { query::strip_tracking(&url) } else { url };
+                                    },
+                                }
+                                FieldError { error: errors.url }
+                                UrlCheck { url: form.read().url.clone() }
+                                QueryBuilder {
+                                    url: form.read().url.clone(),
+                                    onchange: move |url| form.write().url = url,
+                                }
+                            }
+                            span { class: "text-xl", "Never expires" }
+                            div {
+                                input {
+                                    r#type: "checkbox",
+                                    name: "neverExpires",
+                                    cursor: "pointer",
+                                    checked: never_expires,
+                                    onchange: move |e| form.write().never_expires = e.checked(),
                                 }
                             }
                             span { class: "text-xl", "Expiration" }
                             div {
                                 input {
                                     class: "border border-gray-300 px-2",
-                                    r#type: "datetime",
-                                    placeholder: "{expiration}",
+                                    r#type: "datetime-local",
+                                    min: "{min_expiration}",
                                     name: "expiration",
+                                    disabled: never_expires,
+                                    value: "{form.read().expiration}",
+                                    oninput: move |e| form.write().expiration = e.value(),
+                                }
+                                FieldError { error: errors.expiration }
+                            }
+                            span { class: "text-xl", "Time zone" }
+                            div {
+                                TimeZoneSelect {
+                                    value: form.read().time_zone.clone(),
+                                    onchange: move |zone| form.write().time_zone = zone,
                                 }
                             }
                             span { class: "text-xl", "ExpirationTTL" }
@@ -132,9 +238,20 @@ fn link_item_render(link: Link) -> Element {
                                     class: "border border-gray-300 px-2",
                                     r#type: "text",
                                     name: "expirationTtl",
+                                    disabled: never_expires,
+                                    value: "{form.read().expiration_ttl}",
+                                    oninput: move |e| set_ttl(form, e.value()),
+                                }
+                                FieldError { error: errors.expiration_ttl }
+                                if !never_expires {
+                                    TtlExpiry { ttl: form.read().expiration_ttl.clone() }
+                                    TtlPresets { onselect: move |ttl| set_ttl(form, ttl) }
                                 }
                             }
                         }
+                        if let Ok(changes) = changes {
+                            ChangePreview { changes }
+                        }
                         div { class: "grid sm:grid-cols-2",
                             div { class: "mt-1 sm:mt-0",
                                 button {
@@ -150,21 +267,28 @@ fn link_item_render(link: Link) -> Element {
                                     class: "border border-gray-300 hover:bg-gray-200 px-2 text-2xl  text-red-500",
                                     cursor: "pointer",
                                     onclick: {
-                                        let key = key.clone();
+                                        let link = link.clone();
                                         move |_| {
-                                            let key = key.clone();
+                                            let link = link.clone();
                                             async move {
-                                                if !web_sys::window()
-                                                    .unwrap()
-                                                    .confirm_with_message("Are you sure you want to delete this link?")
-                                                    .unwrap()
-                                                {
+                                                if !utils::confirm("Are you sure you want to delete this link?") {
                                                     return;
                                                 }
-                                                if utils::delete_link(key.as_ref()).await {
-                                                    let window = web_sys::window().unwrap();
-                                                    window.alert_with_message("Link deleted").unwrap();
-                                                    use_navigator().replace(Route::Home);
+                                                match history::delete_link(&link, Removal::Deleted).await {
+                                                    Ok(()) => {
+                                                        links.restart();
+                                                        undo::offer(
+                                                            toast,
+                                                            format!("Deleted {}", link.short.key),
+                                                            vec![Undo::Recreate(link)],
+                                                        );
+                                                        use_navigator().replace(Route::Home);
+                                                    }
+                                                    Err(e) => {
+                                                        utils::alert(
+                                                            &format!("Failed to delete the link.\n\nError: {}", e),
+                                                        )
+                                                    }
                                                 }
                                             }
                                         }
@@ -176,6 +300,37 @@ fn link_item_render(link: Link) -> Element {
                     }
                 }
             }
+            div { class: "flex flex-col w-9/12 sm:w-2/3 mx-auto",
+                div { class: "flex flex-col justify-end mx-auto",
+                    if link.url.is_some() {
+                        div { class: "mt-2",
+                            button {
+                                r#type: "button",
+                                class: "border border-gray-300 hover:bg-gray-200 px-2 text-xl",
+                                cursor: "pointer",
+                                onclick: {
+                                    let link = link.clone();
+                                    move |_| {
+                                        draft
+                                            .set(
+                                                LinkForm::duplicate(&link, time::browser_time_zone(), utils::now()),
+                                            );
+                                        use_navigator().push(Route::Create);
+                                    }
+                                },
+                                "Duplicate"
+                            }
+                        }
+                        RenameKey { link: link.clone() }
+                    }
+                    MetaEditor { link: link.short.key.clone() }
+                    SharePanel { short: link.short.clone() }
+                    QrPanel {
+                        text: link.short.full.clone(),
+                        name: link.short.key.clone(),
+                    }
+                }
+            }
         }
     }
 }
@@ -1,14 +1,314 @@
 use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
 
-use crate::utils::{self, Link};
+use crate::{
+    destination,
+    meta::{use_metadata, Meta, MetaTags},
+    query,
+    share::CopyMenu,
+    store::use_links,
+    time::{self, Expiration, Period},
+    utils::{self, Link},
+    views::SaveView,
+    Route,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Bucket {
+    All,
+    WithExpiry,
+    NoExpiry,
+    Expired,
+    Today,
+    Week,
+    Month,
+}
+
+impl Bucket {
+    pub const ALL: [Bucket; 7] = [
+        Bucket::All,
+        Bucket::WithExpiry,
+        Bucket::NoExpiry,
+        Bucket::Expired,
+        Bucket::Today,
+        Bucket::Week,
+        Bucket::Month,
+    ];
+
+    /// The name used in the List URL.
+    pub fn name(self) -> &'static str {
+        match self {
+            Bucket::All => "",
+            Bucket::WithExpiry => "expiring",
+            Bucket::NoExpiry => "permanent",
+            Bucket::Expired => "expired",
+            Bucket::Today => "today",
+            Bucket::Week => "week",
+            Bucket::Month => "month",
+        }
+    }
+
+    pub fn parse(name: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|b| b.name() == name)
+            .unwrap_or(Bucket::All)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Bucket::All => "All links",
+            Bucket::WithExpiry => "With expiry",
+            Bucket::NoExpiry => "Without expiry",
+            Bucket::Expired => "Already expired",
+            Bucket::Today => "Expiring today",
+            Bucket::Week => "Expiring this week",
+            Bucket::Month => "Expiring this month",
+        }
+    }
+
+    pub fn contains(self, link: &Link, now: i64, offset: i32) -> bool {
+        let until = |period| {
+            link.expiration
+                .is_some_and(|e| e > now && e < time::end_of(period, now, offset))
+        };
+        match self {
+            Bucket::All => true,
+            Bucket::WithExpiry => link.expiration.is_some(),
+            Bucket::NoExpiry => link.expiration.is_none(),
+            Bucket::Expired => link.expiration.is_some_and(|e| e <= now),
+            Bucket::Today => until(Period::Day),
+            Bucket::Week => until(Period::Week),
+            Bucket::Month => until(Period::Month),
+        }
+    }
+
+    pub fn route(self, domain: &str) -> Route {
+        Filter {
+            bucket: self.name().into(),
+            domain: domain.into(),
+            ..Default::default()
+        }
+        .route()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Sort {
+    Original,
+    Key,
+    Domain,
+    Expiration,
+}
+
+impl Sort {
+    pub const ALL: [Sort; 4] = [Sort::Original, Sort::Key, Sort::Domain, Sort::Expiration];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Sort::Original => "",
+            Sort::Key => "key",
+            Sort::Domain => "domain",
+            Sort::Expiration => "expiration",
+        }
+    }
+
+    pub fn parse(name: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|s| s.name() == name)
+            .unwrap_or(Sort::Original)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Sort::Original => "Backend order",
+            Sort::Key => "By key",
+            Sort::Domain => "By domain",
+            Sort::Expiration => "By expiration",
+        }
+    }
+
+    /// Stable, so links that compare equal keep the backend order.
+    pub fn sort(self, links: &mut [(Link, Meta)]) {
+        match self {
+            Sort::Original => {}
+            Sort::Key => links.sort_by_key(|(l, _)| l.short.key.to_lowercase()),
+            Sort::Domain => links.sort_by_key(|(l, _)| (domain(l).is_none(), domain(l))),
+            // Links that never expire go last.
+            Sort::Expiration => links.sort_by_key(|(l, _)| (l.expiration.is_none(), l.expiration)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Column {
+    Url,
+    Expiration,
+    Tags,
+    Notes,
+}
+
+impl Column {
+    pub const ALL: [Column; 4] = [Column::Url, Column::Expiration, Column::Tags, Column::Notes];
+    pub const DEFAULT: [Column; 3] = [Column::Url, Column::Tags, Column::Notes];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Column::Url => "url",
+            Column::Expiration => "expiration",
+            Column::Tags => "tags",
+            Column::Notes => "notes",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Column::Url => "URL",
+            Column::Expiration => "Expiration",
+            Column::Tags => "Tags",
+            Column::Notes => "Notes",
+        }
+    }
+
+    /// An empty list means the default columns, `-` means none.
+    pub fn parse_list(columns: &str) -> Vec<Column> {
+        if columns.is_empty() {
+            return Self::DEFAULT.into();
+        }
+        Self::ALL
+            .into_iter()
+            .filter(|c| columns.split(',').any(|n| n == c.name()))
+            .collect()
+    }
+
+    pub fn format_list(columns: &[Column]) -> String {
+        if columns == Self::DEFAULT {
+            String::new()
+        } else if columns.is_empty() {
+            "-".into()
+        } else {
+            columns
+                .iter()
+                .map(|c| c.name())
+                .collect::<Vec<_>>()
+                .join(",")
+        }
+    }
+}
+
+/// What the List shows and how, as carried in its URL.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Filter {
+    pub bucket: String,
+    pub domain: String,
+    pub tag: String,
+    pub search: String,
+    pub starred: bool,
+    pub sort: String,
+    pub columns: String,
+}
+
+impl Filter {
+    pub fn route(&self) -> Route {
+        Route::List {
+            bucket: self.bucket.clone(),
+            domain: self.domain.clone(),
+            tag: self.tag.clone(),
+            search: self.search.clone(),
+            starred: self.starred,
+            sort: self.sort.clone(),
+            columns: self.columns.clone(),
+        }
+    }
+
+    /// The same presentation without narrowing down the links.
+    pub fn unfiltered(&self) -> Self {
+        Self {
+            sort: self.sort.clone(),
+            columns: self.columns.clone(),
+            ..Default::default()
+        }
+    }
+
+    pub fn matches(&self, link: &Link, meta: &Meta, now: i64, offset: i32) -> bool {
+        let search = self.search.trim().to_lowercase();
+        Bucket::parse(&self.bucket).contains(link, now, offset)
+            && (self.domain.is_empty() || domain(link).as_deref() == Some(self.domain.as_str()))
+            && (self.tag.is_empty() || meta.has_tag(&self.tag))
+            && (!self.starred || meta.starred)
+            && (search.is_empty()
+                || link.short.key.to_lowercase().contains(&search)
+                || link
+                    .url
+                    .as_deref()
+                    .is_some_and(|u| u.to_lowercase().contains(&search))
+                || meta.mentions(&search))
+    }
+}
+
+/// The host of a link's destination, used to group and filter by domain.
+pub fn domain(link: &Link) -> Option<String> {
+    let url = destination::normalize(link.url.as_deref()?).ok()?;
+    destination::host(&url).map(String::from)
+}
 
 #[component]
-pub fn List() -> Element {
-    #[allow(clippy::redundant_closure)]
-    let links = use_resource(|| utils::fetch_links());
+pub fn List(
+    bucket: String,
+    domain: String,
+    tag: String,
+    search: String,
+    starred: bool,
+    sort: String,
+    columns: String,
+) -> Element {
+    let links = use_links();
+    let metadata = use_metadata();
+    // The router encodes query values but does not decode them.
+    let filter = Filter {
+        bucket,
+        domain: query::decode(&domain),
+        tag: query::decode(&tag),
+        search: query::decode(&search),
+        starred,
+        sort,
+        columns: query::decode(&columns),
+    };
+    let columns = Column::parse_list(&filter.columns);
+    let now = utils::now();
+    let offset = time::offset(&time::browser_time_zone(), now);
     rsx! {
         match links() {
-            Some(links) => render_links(links.as_ref()),
+            Some(Ok(links)) => {
+                let metadata = metadata.read();
+                let mut filtered: Vec<(Link, Meta)> = links
+                    .iter()
+                    .map(|l| (l.clone(), metadata.get(&l.short.key)))
+                    .filter(|(l, meta)| filter.matches(l, meta, now, offset))
+                    .collect();
+                Sort::parse(&filter.sort).sort(&mut filtered);
+                let bucket = Bucket::parse(&filter.bucket);
+                rsx! {
+                    FilterBar { filter: filter.clone(), tags: metadata.tags() }
+                    if filter != filter.unfiltered() {
+                        p { class: "p-4 border-b border-gray-300",
+                            "{bucket.label()}"
+                            if !filter.domain.is_empty() {
+                                " to {filter.domain}"
+                            }
+                            if !filter.tag.is_empty() {
+                                " tagged {filter.tag}"
+                            }
+                            " ({filtered.len()}) "
+                            Link { class: "underline", to: filter.unfiltered().route(), "Show all" }
+                        }
+                    }
+                    {render_links(&filtered, &columns)}
+                }
+            }
+            Some(Err(e)) => return Err(e.into()),
             None => rsx! {
                 div { class: "mb-2 text-2xl", "Loading..." }
             },
@@ -16,11 +316,111 @@ pub fn List() -> Element {
     }
 }
 
-fn render_links(links: &[Link]) -> Element {
+#[component]
+fn FilterBar(filter: Filter, tags: Vec<Box<str>>) -> Element {
+    let navigator = use_navigator();
+    let columns = Column::parse_list(&filter.columns);
+    let current = filter.clone();
+    let with = move |change: &dyn Fn(&mut Filter)| {
+        let mut filter = current.clone();
+        change(&mut filter);
+        navigator.replace(filter.route());
+    };
+    rsx! {
+        div { class: "flex flex-wrap gap-2 items-center p-4 border-b border-gray-300",
+            input {
+                class: "border border-gray-300 px-2",
+                r#type: "search",
+                placeholder: "Search keys, URLs, tags and notes",
+                value: "{filter.search}",
+                oninput: {
+                    let with = with.clone();
+                    move |e: FormEvent| with(&|f| f.search = e.value())
+                },
+            }
+            select {
+                class: "border border-gray-300 px-2",
+                cursor: "pointer",
+                onchange: {
+                    let with = with.clone();
+                    move |e: FormEvent| with(&|f| f.tag = e.value())
+                },
+                option { value: "", selected: filter.tag.is_empty(), "Any tag" }
+                for tag in tags {
+                    option {
+                        value: "{tag}",
+                        selected: tag.eq_ignore_ascii_case(&filter.tag),
+                        "{tag}"
+                    }
+                }
+            }
+            label { cursor: "pointer",
+                input {
+                    r#type: "checkbox",
+                    checked: filter.starred,
+                    onchange: {
+                        let with = with.clone();
+                        move |e: FormEvent| with(&|f| f.starred = e.checked())
+                    },
+                }
+                " ★ Starred only"
+            }
+            select {
+                class: "border border-gray-300 px-2",
+                cursor: "pointer",
+                onchange: {
+                    let with = with.clone();
+                    move |e: FormEvent| with(&|f| f.sort = e.value())
+                },
+                for sort in Sort::ALL {
+                    option {
+                        value: sort.name(),
+                        selected: sort == Sort::parse(&filter.sort),
+                        "{sort.label()}"
+                    }
+                }
+            }
+            span { class: "text-sm text-gray-500", "Show" }
+            for column in Column::ALL {
+                label { class: "text-sm", cursor: "pointer",
+                    input {
+                        r#type: "checkbox",
+                        checked: columns.contains(&column),
+                        onchange: {
+                            let with = with.clone();
+                            let columns = columns.clone();
+                            move |e: FormEvent| {
+                                let columns: Vec<Column> = Column::ALL
+                                    .into_iter()
+                                    .filter(|c| if *c == column { e.checked() } else { columns.contains(c) })
+                                    .collect();
+                                with(&|f| f.columns = Column::format_list(&columns))
+                            }
+                        },
+                    }
+                    " {column.label()}"
+                }
+            }
+            SaveView { filter: filter.clone() }
+        }
+    }
+}
+
+fn render_links(links: &[(Link, Meta)], columns: &[Column]) -> Element {
     rsx! {
-        for link in links.iter() {
-            Link { to: format!("/link/{}", link.short.key),
-                LinkComponent { link: link.clone() }
+        for (link , meta) in links.iter() {
+            // The copy button sits next to the link so clicking it does not navigate.
+            div { class: "flex items-start border-r border-b border-gray-300 hover:bg-gray-100",
+                Link { class: "flex-1", to: format!("/link/{}", link.short.key),
+                    LinkComponent {
+                        link: link.clone(),
+                        meta: meta.clone(),
+                        columns: columns.to_vec(),
+                    }
+                }
+                div { class: "p-4",
+                    CopyMenu { short: link.short.clone() }
+                }
             }
         }
         if links.is_empty() {
@@ -30,14 +430,156 @@ fn render_links(links: &[Link]) -> Element {
 }
 
 #[component]
-fn LinkComponent(link: Link) -> Element {
+fn LinkComponent(link: Link, meta: Meta, columns: Vec<Column>) -> Element {
     let key = link.short.key;
-    let url = link.url.unwrap_or(Box::from("Failed to display"));
+    let notes = meta.notes.clone();
     rsx! {
-        div { class: "border-r border-b border-gray-300 p-4 hover:bg-gray-100",
-            p { class: "mb-2", "Key: {key}" }
-            p { class: "mb-2 break-all", "To URL:
-                {url}" }
+        div { class: "p-4",
+            p { class: "mb-2",
+                if columns.contains(&Column::Tags) {
+                    MetaTags { meta }
+                }
+                "Key: {key}"
+            }
+            match link.url {
+                Some(url) if columns.contains(&Column::Url) => rsx! {
+                    p { class: "mb-2 break-all", "To URL:
+                        {url}" }
+                },
+                Some(_) => rsx! {},
+                None => rsx! {
+                    p { class: "mb-2 text-red-500", "Broken: no destination URL, open to repair" }
+                },
+            }
+            if columns.contains(&Column::Expiration) {
+                p { class: "mb-2",
+                    "Expires: "
+                    match link.expiration {
+                        Some(timestamp) => rsx! {
+                            Expiration { timestamp }
+                        },
+                        None => rsx! { "never" },
+                    }
+                }
+            }
+            if columns.contains(&Column::Notes) && !notes.trim().is_empty() {
+                p { class: "text-sm text-gray-500 truncate", "{notes}" }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Monday 2026-10-19 12:30 UTC.
+    const NOW: i64 = 1_792_413_000;
+    const HOUR: i64 = 3600;
+    const DAY: i64 = 24 * HOUR;
+
+    fn buckets(expiration: Option<i64>) -> Vec<Bucket> {
+        let link = utils::test_link("a", Some("https://a.b"), expiration);
+        Bucket::ALL
+            .into_iter()
+            .filter(|b| b.contains(&link, NOW, 0))
+            .collect()
+    }
+
+    #[test]
+    fn bucketing() {
+        use Bucket::*;
+        assert_eq!(buckets(None), [All, NoExpiry]);
+        assert_eq!(buckets(Some(NOW - 1)), [All, WithExpiry, Expired]);
+        assert_eq!(
+            buckets(Some(NOW + HOUR)),
+            [All, WithExpiry, Today, Week, Month]
+        );
+        assert_eq!(buckets(Some(NOW + 2 * DAY)), [All, WithExpiry, Week, Month]);
+        assert_eq!(buckets(Some(NOW + 8 * DAY)), [All, WithExpiry, Month]);
+        assert_eq!(buckets(Some(NOW + 40 * DAY)), [All, WithExpiry]);
+    }
+
+    #[test]
+    fn names() {
+        for bucket in Bucket::ALL {
+            assert_eq!(Bucket::parse(bucket.name()), bucket);
+        }
+        assert_eq!(Bucket::parse("nonsense"), Bucket::All);
+    }
+
+    #[test]
+    fn domains() {
+        let link = utils::test_link("a", Some("HTTPS://Www.A.b/x"), None);
+        assert_eq!(domain(&link).as_deref(), Some("www.a.b"));
+        assert_eq!(domain(&utils::test_link("a", None, None)), None);
+    }
+
+    #[test]
+    fn filtering() {
+        let link = utils::test_link("Promo", Some("https://shop.a.b/sale"), None);
+        let meta = Meta {
+            tags: vec!["Spring".into()],
+            notes: "Newsletter".into(),
+            starred: false,
+        };
+        let matches = |filter: Filter| filter.matches(&link, &meta, NOW, 0);
+        assert!(matches(Filter::default()));
+        assert!(matches(Filter {
+            tag: "spring".into(),
+            ..Default::default()
+        }));
+        assert!(!matches(Filter {
+            starred: true,
+            ..Default::default()
+        }));
+        for search in ["promo", "SALE", "spr", "newsletter"] {
+            assert!(matches(Filter {
+                search: search.into(),
+                ..Default::default()
+            }));
+        }
+        assert!(!matches(Filter {
+            search: "fall".into(),
+            ..Default::default()
+        }));
+        assert!(!matches(Filter {
+            bucket: Bucket::Expired.name().into(),
+            ..Default::default()
+        }));
+    }
+
+    #[test]
+    fn sorting() {
+        let entry =
+            |key, url, expiration| (utils::test_link(key, url, expiration), Meta::default());
+        let mut links = vec![
+            entry("b", Some("https://z.z"), None),
+            entry("C", None, Some(NOW + DAY)),
+            entry("a", Some("https://y.y"), Some(NOW + HOUR)),
+        ];
+        let keys = |links: &[(Link, Meta)]| -> Vec<String> {
+            links.iter().map(|(l, _)| l.short.key.to_string()).collect()
+        };
+        Sort::Key.sort(&mut links);
+        assert_eq!(keys(&links), ["a", "b", "C"]);
+        Sort::Domain.sort(&mut links);
+        assert_eq!(keys(&links), ["a", "b", "C"]);
+        Sort::Expiration.sort(&mut links);
+        assert_eq!(keys(&links), ["a", "C", "b"]);
+        assert_eq!(Sort::parse("expiration"), Sort::Expiration);
+    }
+
+    #[test]
+    fn columns() {
+        assert_eq!(Column::parse_list(""), Column::DEFAULT);
+        assert_eq!(Column::parse_list("-"), []);
+        assert_eq!(
+            Column::parse_list("notes,url,bogus"),
+            [Column::Url, Column::Notes]
+        );
+        for columns in [&Column::DEFAULT[..], &[], &[Column::Expiration]] {
+            assert_eq!(Column::parse_list(&Column::format_list(columns)), columns);
         }
     }
 }
@@ -2,6 +2,8 @@ use std::rc::Rc;
 
 use dioxus::prelude::*;
 
+use crate::{error::Result, utils};
+
 #[component]
 pub fn LoginForm() -> Element {
     rsx! {
@@ -45,40 +47,37 @@ pub fn LoginForm() -> Element {
 }
 
 pub async fn check_local_login_info() -> bool {
-    let storage = web_sys::window().unwrap().local_storage().unwrap().unwrap();
-    let url = storage.get_item("backendUrl").unwrap();
-    let token = storage.get_item("token").unwrap();
-    if url.is_none() || token.is_none() {
+    let Ok(storage) = utils::storage() else {
         return false;
+    };
+    match (storage.get_item("backendUrl"), storage.get_item("token")) {
+        (Ok(Some(url)), Ok(Some(token))) => check_login_info(url, token).await,
+        _ => false,
     }
-    check_login_info(url.unwrap(), token.unwrap()).await
 }
 
 async fn login_handler(url: impl Into<Rc<str>>, token: impl Into<Rc<str>>) {
-    let window = web_sys::window().unwrap();
     let url: Rc<str> = url.into();
     let token: Rc<str> = token.into();
     if url.is_empty() || token.is_empty() {
-        window
-            .alert_with_message("Please fill in both fields.")
-            .unwrap();
+        utils::alert("Please fill in both fields.");
         return;
     }
     if !url.starts_with("http://") && !url.starts_with("https://") {
-        window
-            .alert_with_message("Please enter a valid URL starting with http:// or https://")
-            .unwrap();
+        utils::alert("Please enter a valid URL starting with http:// or https://");
         return;
     }
     let url = url.trim_end_matches('/');
     if !check_login_info(url, token.as_ref()).await {
-        window
-            .alert_with_message("Invalid login information. Please try again.")
-            .unwrap();
+        utils::alert("Invalid login information. Please try again.");
+    } else if let Err(e) = store_login_info(url, token.as_ref()) {
+        utils::alert(&format!(
+            "Failed to store the login information.\n\nError: {}",
+            e
+        ));
     } else {
-        store_login_info(url, token.as_ref());
-        window.alert_with_message("Login successful!").unwrap();
-        window.location().reload().unwrap();
+        utils::alert("Login successful!");
+        utils::reload();
     }
 }
 
@@ -94,10 +93,11 @@ async fn check_login_info(url: impl Into<Box<str>>, token: impl Into<Box<str>>)
         .unwrap_or(false)
 }
 
-fn store_login_info(url: impl Into<Rc<str>>, token: impl Into<Rc<str>>) {
+fn store_login_info(url: impl Into<Rc<str>>, token: impl Into<Rc<str>>) -> Result<()> {
     let url: Rc<str> = url.into();
     let token: Rc<str> = token.into();
-    let storage = web_sys::window().unwrap().local_storage().unwrap().unwrap();
-    storage.set_item("backendUrl", &url).unwrap();
-    storage.set_item("token", &token).unwrap();
+    let storage = utils::storage()?;
+    storage.set_item("backendUrl", &url)?;
+    storage.set_item("token", &token)?;
+    Ok(())
 }
@@ -1,23 +1,59 @@
-#![cfg(target_arch = "wasm32")]
+#![cfg(any(target_arch = "wasm32", test))]
+mod calendar;
+mod cleanup;
 mod create;
+mod dashboard;
+mod destination;
+mod duplicates;
+mod duration;
+mod error;
+mod extend;
+mod form;
+mod history;
+mod ics;
 mod link;
 mod list;
 mod login;
+mod meta;
+mod policy;
+mod qr;
+mod query;
+mod rename;
+mod settings;
+mod share;
+mod store;
+mod time;
+mod trash;
+mod undo;
 mod utils;
+mod views;
 
 use dioxus::prelude::*;
 
 use crate::{
+    calendar::Calendar,
+    cleanup::Cleanup,
     create::Create,
+    dashboard::Dashboard,
+    duplicates::Duplicates,
+    extend::Extend,
+    history::History,
     link::LinkItem,
-    list::List,
+    list::{Bucket, List},
     login::{check_local_login_info, LoginForm},
+    policy::PolicyPage,
+    qr::QrSheet,
+    settings::{Settings, SettingsPage},
+    trash::Trash,
 };
 
 const TAILWIND_CSS: Asset = asset!("/assets/tailwind.css");
 const FAVICON: Asset = asset!("/assets/favicon.svg");
 
 fn main() {
+    // The logger installs its own panic hook, so ours has to come after it.
+    dioxus::logger::initialize_default();
+    error::install_panic_hook();
     launch(App);
 }
 
@@ -25,6 +61,7 @@ fn main() {
 fn App() -> Element {
     #[allow(clippy::redundant_closure)]
     let checked = use_resource(|| check_local_login_info());
+    use_context_provider(|| Signal::new(Settings::load()));
     rsx! {
         link { rel: "icon", href: FAVICON, r#type: "image/svg+xml" }
         document::Stylesheet { href: TAILWIND_CSS }
@@ -42,12 +79,38 @@ enum Route {
     #[layout(SideBar)]
     #[route("/")]
     Home,
-    #[route("/list")]
-    List,
+    #[route("/list?:bucket&:domain&:tag&:search&:starred&:sort&:columns")]
+    List {
+        bucket: String,
+        domain: String,
+        tag: String,
+        search: String,
+        starred: bool,
+        sort: String,
+        columns: String,
+    },
     #[route("/link/:link")]
     LinkItem { link: String },
     #[route("/create")]
     Create,
+    #[route("/calendar?:month")]
+    Calendar { month: String },
+    #[route("/extend")]
+    Extend,
+    #[route("/cleanup")]
+    Cleanup,
+    #[route("/duplicates")]
+    Duplicates,
+    #[route("/history")]
+    History,
+    #[route("/trash")]
+    Trash,
+    #[route("/policy")]
+    PolicyPage,
+    #[route("/qr")]
+    QrSheet,
+    #[route("/settings")]
+    SettingsPage,
     #[route("/:..s")]
     NotFound { s: Vec<String> },
 }
@@ -55,7 +118,7 @@ enum Route {
 #[component]
 pub fn NavBar() -> Element {
     rsx! {
-        header { class: "flex justify-between items-center border border-gray-300 bg-white sticky top-0",
+        header { class: "flex justify-between items-center border border-gray-300 bg-white sticky top-0 print:hidden",
             span { class: "pe-px" }
             h1 { class: "text-xl font-semibold text-gray-800", "linkrusk" }
             div { class: "flex items-stretch h-7 hover:bg-gray-200",
@@ -63,12 +126,14 @@ pub fn NavBar() -> Element {
                 Link {
                     to: Route::Home,
                     onclick: |_| {
-                        let window = web_sys::window().unwrap();
-                        if !window.confirm_with_message("Are you sure you want to logout?").unwrap() {
+                        if !utils::confirm("Are you sure you want to logout?") {
                             return;
                         }
-                        window.local_storage().unwrap().unwrap().clear().unwrap();
-                        window.location().reload().unwrap();
+                        if let Err(e) = utils::logout() {
+                            utils::alert(&format!("Failed to logout.\n\nError: {}", e));
+                            return;
+                        }
+                        utils::reload();
                     },
                     "Logout"
                 }
@@ -81,24 +146,111 @@ pub fn NavBar() -> Element {
 
 #[component]
 fn SideBar() -> Element {
+    let route = use_route::<Route>();
+    store::use_links_provider();
+    form::use_draft_provider();
+    policy::use_policy_provider();
+    meta::use_metadata_provider();
+    views::use_views_provider();
+    undo::use_undo_provider();
     rsx! {
         aside { class: "w-20 bg-white border-r border-b border-gray-300
-                        fixed top-7.1 left-0 h-full flex flex-col",
+                        fixed top-7.1 left-0 h-full flex flex-col print:hidden",
             nav {
                 ul {
                     li { class: "flex items-stretch h-7 w-20 hover:bg-gray-200 border-b border-gray-300",
                         Link { class: "px-4.5", to: Route::Home, "Home" }
                     }
                     li { class: "flex items-stretch h-7 w-20 hover:bg-gray-200 border-b border-gray-300",
-                        Link { class: "px-4.5", to: Route::List, "List" }
+                        Link { class: "px-4.5", to: Bucket::All.route(""), "List" }
                     }
+                    views::ViewLinks {}
                     li { class: "flex items-stretch h-7 w-20 hover:bg-gray-200 border-b border-gray-300",
                         Link { class: "px-4.5", to: Route::Create, "Create" }
                     }
+                    li { class: "flex items-stretch h-7 w-20 hover:bg-gray-200 border-b border-gray-300",
+                        Link {
+                            class: "px-4.5",
+                            to: Route::Calendar {
+                                month: String::new(),
+                            },
+                            "Calendar"
+                        }
+                    }
+                    li { class: "flex items-stretch h-7 w-20 hover:bg-gray-200 border-b border-gray-300",
+                        Link { class: "px-4.5", to: Route::Extend, "Extend" }
+                    }
+                    li { class: "flex items-stretch h-7 w-20 hover:bg-gray-200 border-b border-gray-300",
+                        Link { class: "px-4.5", to: Route::Cleanup, "Purge" }
+                    }
+                    li { class: "flex items-stretch h-7 w-20 hover:bg-gray-200 border-b border-gray-300",
+                        Link {
+                            class: "px-4.5",
+                            to: Route::Duplicates,
+                            title: "Duplicate destinations",
+                            "Dupes"
+                        }
+                    }
+                    li { class: "flex items-stretch h-7 w-20 hover:bg-gray-200 border-b border-gray-300",
+                        Link { class: "px-4.5", to: Route::QrSheet, "QR" }
+                    }
+                    li { class: "flex items-stretch h-7 w-20 hover:bg-gray-200 border-b border-gray-300",
+                        Link { class: "px-4.5", to: Route::PolicyPage, "Policy" }
+                    }
+                    li { class: "flex items-stretch h-7 w-20 hover:bg-gray-200 border-b border-gray-300",
+                        Link { class: "px-4.5", to: Route::History, "History" }
+                    }
+                    li { class: "flex items-stretch h-7 w-20 hover:bg-gray-200 border-b border-gray-300",
+                        Link { class: "px-4.5", to: Route::Trash, "Trash" }
+                    }
+                    li { class: "flex items-stretch h-7 w-20 hover:bg-gray-200 border-b border-gray-300",
+                        Link { class: "px-4.5", to: Route::SettingsPage, "Settings" }
+                    }
+                }
+            }
+        }
+        div { class: "pl-20 print:pl-0",
+            ErrorBoundary {
+                // Remount on navigation so an error on one page does not stick to the next.
+                key: "{route}",
+                handle_error: |errors: ErrorContext| rsx! {
+                    ErrorScreen { errors }
+                },
+                Outlet::<Route> {}
+            }
+        }
+        undo::UndoToast {}
+    }
+}
+
+#[component]
+fn ErrorScreen(errors: ErrorContext) -> Element {
+    let details = errors
+        .errors()
+        .iter()
+        .map(|e| e.to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+    rsx! {
+        div { class: "flex flex-col w-9/12 sm:w-2/3 mx-auto mt-5",
+            h1 { class: "text-3xl", "Something went wrong" }
+            p { class: "mt-2", "If this keeps happening, please copy the details below into a bug report." }
+            textarea {
+                class: "border border-gray-300 px-2 mt-2 font-mono",
+                rows: 6,
+                resize: "none",
+                readonly: true,
+                value: "{details}",
+            }
+            div { class: "mt-2",
+                button {
+                    class: "border border-gray-300 hover:bg-gray-200 px-2 text-xl",
+                    cursor: "pointer",
+                    onclick: |_| utils::reload(),
+                    "Reload"
                 }
             }
         }
-        div { class: "pl-20", Outlet::<Route> {} }
     }
 }
 
@@ -111,6 +263,7 @@ pub fn Home() -> Element {
                 "Welcome to linkrusk!"
             }
         }
+        Dashboard {}
     }
 }
 
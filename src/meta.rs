@@ -0,0 +1,322 @@
+use std::collections::BTreeMap;
+
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{error::Result, utils};
+
+/// Notes the backend knows nothing about, kept in this browser.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Meta {
+    pub tags: Vec<Box<str>>,
+    pub notes: String,
+    pub starred: bool,
+}
+
+impl Meta {
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.notes.trim().is_empty() && !self.starred
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+
+    /// Case-insensitive search in the tags and notes.
+    pub fn mentions(&self, needle: &str) -> bool {
+        self.tags.iter().any(|t| t.to_lowercase().contains(needle))
+            || self.notes.to_lowercase().contains(needle)
+    }
+}
+
+/// The metadata of every link of a backend, by key.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Metadata {
+    pub links: BTreeMap<Box<str>, Meta>,
+}
+
+impl Metadata {
+    pub fn load() -> Self {
+        utils::load(&utils::backend_storage_key("meta"))
+            .ok()
+            .flatten()
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        utils::save(&utils::backend_storage_key("meta"), self)
+    }
+
+    pub fn get(&self, key: &str) -> Meta {
+        self.links.get(key).cloned().unwrap_or_default()
+    }
+
+    pub fn set(&mut self, key: &str, meta: Meta) {
+        if meta.is_empty() {
+            self.links.remove(key);
+        } else {
+            self.links.insert(key.into(), meta);
+        }
+    }
+
+    /// Moves the metadata of a renamed link to its new key.
+    pub fn rename(&mut self, from: &str, to: &str) {
+        if let Some(meta) = self.links.remove(from) {
+            self.links.insert(to.into(), meta);
+        }
+    }
+
+    /// Drops the metadata of keys that should no longer have any. Returns
+    /// whether something was dropped.
+    pub fn retain(&mut self, keep: impl Fn(&str) -> bool) -> bool {
+        let before = self.links.len();
+        self.links.retain(|key, _| keep(key));
+        self.links.len() != before
+    }
+
+    /// Every tag in use, sorted and without case-insensitive duplicates.
+    pub fn tags(&self) -> Vec<Box<str>> {
+        let mut tags: Vec<Box<str>> = Vec::new();
+        for tag in self.links.values().flat_map(|m| &m.tags) {
+            if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                tags.push(tag.clone());
+            }
+        }
+        tags.sort_by_key(|t| t.to_lowercase());
+        tags
+    }
+
+    pub fn export(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /// Merges an export into this metadata, imported entries win. Returns the
+    /// number of imported links.
+    pub fn import(&mut self, json: &str) -> std::result::Result<usize, Box<str>> {
+        let imported: Metadata =
+            serde_json::from_str(json).map_err(|e| format!("Not a metadata export: {}", e))?;
+        let count = imported.links.len();
+        for (key, meta) in imported.links {
+            self.set(&key, meta);
+        }
+        Ok(count)
+    }
+}
+
+pub fn parse_tags(tags: &str) -> Vec<Box<str>> {
+    let mut parsed: Vec<Box<str>> = Vec::new();
+    for tag in tags.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        if !parsed.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            parsed.push(tag.into());
+        }
+    }
+    parsed
+}
+
+pub fn use_metadata_provider() -> Signal<Metadata> {
+    use_context_provider(|| Signal::new(Metadata::load()))
+}
+
+pub fn use_metadata() -> Signal<Metadata> {
+    use_context()
+}
+
+#[component]
+pub fn MetaEditor(link: Box<str>) -> Element {
+    let mut metadata = use_metadata();
+    let saved = metadata.read().get(&link);
+    let mut starred = use_signal(|| saved.starred);
+    let mut tags = use_signal(|| saved.tags.join(", "));
+    let mut notes = use_signal(|| saved.notes.clone());
+    let meta = Meta {
+        tags: parse_tags(&tags.read()),
+        notes: notes(),
+        starred: starred(),
+    };
+    let changed = meta != saved;
+    rsx! {
+        div { class: "mt-2",
+            p { class: "text-xl", "Local notes" }
+            p { class: "text-sm text-gray-500", "Only stored in this browser, move them with the export on Settings." }
+            label { class: "block", cursor: "pointer",
+                input {
+                    r#type: "checkbox",
+                    checked: starred(),
+                    onchange: move |e| starred.set(e.checked()),
+                }
+                " ★ Starred"
+            }
+            input {
+                class: "border border-gray-300 px-2 block",
+                r#type: "text",
+                placeholder: "Tags, separated by commas",
+                value: "{tags}",
+                oninput: move |e| tags.set(e.value()),
+            }
+            textarea {
+                class: "border border-gray-300 px-2 block",
+                rows: 3,
+                resize: "none",
+                placeholder: "Notes",
+                value: "{notes}",
+                oninput: move |e| notes.set(e.value()),
+            }
+            button {
+                r#type: "button",
+                class: "border border-gray-300 hover:bg-gray-200 px-2",
+                cursor: "pointer",
+                disabled: !changed,
+                onclick: move |_| {
+                    let mut new = metadata.read().clone();
+                    new.set(&link, meta.clone());
+                    match new.save() {
+                        Ok(()) => {
+                            tags.set(meta.tags.join(", "));
+                            metadata.set(new);
+                        }
+                        Err(e) => utils::alert(&format!("Failed to save the notes.\n\nError: {}", e)),
+                    }
+                },
+                if changed {
+                    "Save notes"
+                } else {
+                    "Saved"
+                }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn MetaTags(meta: Meta) -> Element {
+    rsx! {
+        if meta.starred {
+            span { class: "text-yellow-500", title: "Starred", "★ " }
+        }
+        for tag in meta.tags {
+            span { class: "border border-gray-300 px-1 mr-1 text-sm", "{tag}" }
+        }
+    }
+}
+
+#[component]
+pub fn MetaTransfer() -> Element {
+    let mut metadata = use_metadata();
+    let count = metadata.read().links.len();
+    let backend = utils::backend_host().unwrap_or_default();
+    rsx! {
+        div { class: "flex flex-wrap gap-1 items-center",
+            button {
+                r#type: "button",
+                class: "border border-gray-300 hover:bg-gray-200 px-2",
+                cursor: "pointer",
+                onclick: move |_| {
+                    utils::export(
+                        &format!("linkrusk-metadata-{}.json", backend),
+                        "application/json",
+                        &metadata.read().export(),
+                    )
+                },
+                "Export ({count} links)"
+            }
+            label { class: "border border-gray-300 hover:bg-gray-200 px-2", cursor: "pointer",
+                "Import"
+                input {
+                    class: "hidden",
+                    r#type: "file",
+                    accept: "application/json,.json",
+                    onchange: move |e| async move {
+                        let Some(engine) = e.files() else {
+                            return;
+                        };
+                        for file in engine.files() {
+                            let Some(json) = engine.read_file_to_string(&file).await else {
+                                continue;
+                            };
+                            let mut new = metadata.read().clone();
+                            let result = new.import(&json).and_then(|count| {
+                                new.save().map(|()| count).map_err(|e| e.to_string().into())
+                            });
+                            match result {
+                                Ok(count) => {
+                                    metadata.set(new);
+                                    utils::alert(&format!("Imported the notes of {} links", count));
+                                }
+                                Err(e) => utils::alert(&format!("Failed to import {}.\n\nError: {}", file, e)),
+                            }
+                        }
+                    },
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meta(tags: &[&str], starred: bool) -> Meta {
+        Meta {
+            tags: tags.iter().map(|t| Box::from(*t)).collect(),
+            notes: String::new(),
+            starred,
+        }
+    }
+
+    #[test]
+    fn tags() {
+        assert_eq!(
+            parse_tags(" Spring, promo,,spring ,Fall"),
+            [Box::from("Spring"), "promo".into(), "Fall".into()]
+        );
+        let mut metadata = Metadata::default();
+        metadata.set("a", meta(&["promo", "Fall"], false));
+        metadata.set("b", meta(&["PROMO", "ads"], false));
+        assert_eq!(
+            metadata.tags(),
+            [Box::from("ads"), "Fall".into(), "promo".into()]
+        );
+        assert!(metadata.get("b").has_tag("promo"));
+    }
+
+    #[test]
+    fn empty_entries_are_dropped() {
+        let mut metadata = Metadata::default();
+        metadata.set("a", meta(&[], true));
+        assert!(metadata.get("a").starred);
+        metadata.set("a", meta(&[], false));
+        assert!(metadata.links.is_empty());
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut metadata = Metadata::default();
+        metadata.set("a", meta(&["x"], true));
+        metadata.set("b", meta(&["y"], false));
+        let mut other = Metadata::default();
+        other.set("a", meta(&["old"], false));
+        other.set("c", meta(&["z"], false));
+        assert_eq!(other.import(&metadata.export()), Ok(2));
+        assert_eq!(other.get("a"), meta(&["x"], true));
+        assert_eq!(other.get("c"), meta(&["z"], false));
+        assert!(other.import("[]").is_err());
+        assert!(other.import("{}").is_err());
+    }
+
+    #[test]
+    fn moving_and_dropping() {
+        let mut metadata = Metadata::default();
+        metadata.set("old", meta(&["x"], true));
+        metadata.set("gone", meta(&["y"], false));
+        metadata.rename("old", "new");
+        assert!(metadata.get("old").is_empty());
+        assert_eq!(metadata.get("new"), meta(&["x"], true));
+        metadata.rename("missing", "other");
+        assert!(metadata.get("other").is_empty());
+        assert!(metadata.retain(|key| key == "new"));
+        assert!(!metadata.retain(|key| key == "new"));
+        assert_eq!(metadata.links.len(), 1);
+    }
+}
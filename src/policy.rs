@@ -0,0 +1,310 @@
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    destination,
+    error::Result,
+    form::FieldError,
+    store::use_links,
+    utils::{self, Link},
+    Route,
+};
+
+/// Allowed and blocked domain patterns. A pattern matches the domain itself
+/// and all of its subdomains, `*.example.com` only matches the subdomains.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Policy {
+    pub allowed: Vec<Box<str>>,
+    pub blocked: Vec<Box<str>>,
+}
+
+impl Policy {
+    pub fn load() -> Self {
+        utils::load(&utils::backend_storage_key("policy"))
+            .ok()
+            .flatten()
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        utils::save(&utils::backend_storage_key("policy"), self)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.allowed.is_empty() && self.blocked.is_empty()
+    }
+
+    /// Checks a destination URL, normalizing it first.
+    pub fn check(&self, url: &str) -> std::result::Result<(), Box<str>> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        let url = destination::normalize(url)?;
+        let Some(host) = destination::host(&url) else {
+            return Err("The domain policy only allows URLs with a domain".into());
+        };
+        if let Some(pattern) = self.blocked.iter().find(|p| matches(p, host)) {
+            return Err(format!("{} is blocked by the domain policy ({})", host, pattern).into());
+        }
+        if !self.allowed.is_empty() && !self.allowed.iter().any(|p| matches(p, host)) {
+            return Err(format!("{} is not on the list of allowed domains", host).into());
+        }
+        Ok(())
+    }
+
+    /// Checks the destination a key is about to point to, given the one it
+    /// points to now. Keeping the destination is never refused, so changing
+    /// only the expiration of a link that predates the policy still works.
+    pub fn check_change(
+        &self,
+        current: Option<&str>,
+        url: &str,
+    ) -> std::result::Result<(), Box<str>> {
+        let unchanged = current.is_some_and(|current| {
+            current == url
+                || matches!(
+                    (destination::normalize(current), destination::normalize(url)),
+                    (Ok(a), Ok(b)) if a == b
+                )
+        });
+        if unchanged {
+            Ok(())
+        } else {
+            self.check(url)
+        }
+    }
+}
+
+fn matches(pattern: &str, host: &str) -> bool {
+    match pattern.strip_prefix("*.") {
+        Some(domain) => host
+            .strip_suffix(domain)
+            .is_some_and(|sub| sub.len() > 1 && sub.ends_with('.')),
+        None => {
+            host == pattern
+                || host
+                    .strip_suffix(pattern)
+                    .is_some_and(|sub| sub.ends_with('.'))
+        }
+    }
+}
+
+fn parse_patterns(patterns: &str) -> std::result::Result<Vec<Box<str>>, Box<str>> {
+    patterns
+        .lines()
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(|p| {
+            let (prefix, domain) = match p.strip_prefix("*.") {
+                Some(domain) => ("*.", domain),
+                None => ("", p),
+            };
+            idna::domain_to_ascii(domain)
+                .ok()
+                .filter(|d| !d.is_empty() && !d.contains(['*', '/', ':']))
+                .map(|d| format!("{}{}", prefix, d).into())
+                .ok_or_else(|| format!("{}: not a domain pattern", p).into())
+        })
+        .collect()
+}
+
+pub fn use_policy_provider() -> Signal<Policy> {
+    use_context_provider(|| Signal::new(Policy::load()))
+}
+
+pub fn use_policy() -> Signal<Policy> {
+    use_context()
+}
+
+pub fn violations<'a>(policy: &Policy, links: &'a [Link]) -> Vec<(&'a Link, Box<str>)> {
+    links
+        .iter()
+        .filter_map(|link| {
+            let url = link.url.as_deref()?;
+            policy.check(url).err().map(|e| (link, e))
+        })
+        .collect()
+}
+
+#[component]
+pub fn PolicyPage() -> Element {
+    let mut policy = use_policy();
+    let links = use_links();
+    let mut allowed = use_signal(|| policy.read().allowed.join("\n"));
+    let mut blocked = use_signal(|| policy.read().blocked.join("\n"));
+    let allowed_error = parse_patterns(&allowed.read()).err();
+    let blocked_error = parse_patterns(&blocked.read()).err();
+    let backend = utils::backend_host().unwrap_or_default();
+    rsx! {
+        div {
+            div { class: "flex flex-col",
+                h1 { class: "text-3xl mx-auto", "Domain policy" }
+                p { class: "mx-auto text-gray-500", "For {backend}" }
+            }
+            form {
+                class: "mt-5",
+                onsubmit: move |_| {
+                    let (Ok(allowed), Ok(blocked)) = (
+                        parse_patterns(&allowed.read()),
+                        parse_patterns(&blocked.read()),
+                    ) else {
+                        return;
+                    };
+                    let new = Policy { allowed, blocked };
+                    match new.save() {
+                        Ok(()) => {
+                            policy.set(new);
+                            utils::alert("Policy saved");
+                        }
+                        Err(e) => utils::alert(&format!("Failed to save the policy.\n\nError: {}", e)),
+                    }
+                },
+                div { class: "flex flex-col w-9/12 sm:w-2/3 mx-auto",
+                    div { class: "grid sm:grid-cols-2",
+                        div {
+                            p { class: "text-xl", "Allowed domains" }
+                            p { class: "text-sm text-gray-500",
+                                "One per line. When empty, every domain that is not blocked is allowed."
+                            }
+                        }
+                        div {
+                            textarea {
+                                class: "border border-gray-300 px-2",
+                                rows: 6,
+                                resize: "none",
+                                name: "allowed",
+                                placeholder: "example.com\n*.example.org",
+                                value: "{allowed}",
+                                oninput: move |e| allowed.set(e.value()),
+                            }
+                            FieldError { error: allowed_error }
+                        }
+                        div {
+                            p { class: "text-xl", "Blocked domains" }
+                            p { class: "text-sm text-gray-500", "Blocking wins over allowing." }
+                        }
+                        div {
+                            textarea {
+                                class: "border border-gray-300 px-2",
+                                rows: 6,
+                                resize: "none",
+                                name: "blocked",
+                                value: "{blocked}",
+                                oninput: move |e| blocked.set(e.value()),
+                            }
+                            FieldError { error: blocked_error }
+                        }
+                    }
+                    div { class: "mt-1 mx-auto",
+                        button {
+                            class: "border border-gray-300 hover:bg-gray-200 px-2 text-2xl",
+                            cursor: "pointer",
+                            "Save"
+                        }
+                    }
+                }
+            }
+            div { class: "flex flex-col w-9/12 sm:w-2/3 mx-auto mt-5",
+                h2 { class: "text-xl", "Links that violate the saved policy" }
+                match &*links.read() {
+                    Some(Ok(links)) => {
+                        let violations = violations(&policy.read(), links);
+                        rsx! {
+                            if violations.is_empty() {
+                                p { class: "text-gray-500", "None." }
+                            }
+                            for (link , error) in violations {
+                                Link {
+                                    class: "border-b border-gray-300 hover:bg-gray-100 break-all",
+                                    to: Route::LinkItem {
+                                        link: link.short.key.to_string(),
+                                    },
+                                    p { "{link.short.key}: {link.url.as_deref().unwrap_or_default()}" }
+                                    p { class: "text-sm text-red-500", "{error}" }
+                                }
+                            }
+                        }
+                    }
+                    Some(Err(e)) => return Err(e.clone().into()),
+                    None => rsx! {
+                        p { "Loading..." }
+                    },
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(allowed: &str, blocked: &str) -> Policy {
+        Policy {
+            allowed: parse_patterns(allowed).unwrap(),
+            blocked: parse_patterns(blocked).unwrap(),
+        }
+    }
+
+    #[test]
+    fn patterns() {
+        assert_eq!(
+            parse_patterns(" Example.com \n\n*.Bücher.de").unwrap(),
+            ["example.com", "*.xn--bcher-kva.de"].map(Box::from)
+        );
+        assert!(parse_patterns("https://example.com").is_err());
+        assert!(parse_patterns("a.*.com").is_err());
+    }
+
+    #[test]
+    fn matching() {
+        assert!(matches("example.com", "example.com"));
+        assert!(matches("example.com", "www.example.com"));
+        assert!(!matches("example.com", "badexample.com"));
+        assert!(!matches("*.example.com", "example.com"));
+        assert!(matches("*.example.com", "a.example.com"));
+    }
+
+    #[test]
+    fn checking() {
+        assert!(Policy::default().check("anything").is_ok());
+        let p = policy("example.com", "evil.example.com");
+        assert!(p.check("https://example.com/a").is_ok());
+        assert!(p.check("WWW.example.com").is_ok());
+        assert!(p.check("https://evil.example.com").is_err());
+        assert!(p.check("https://other.org").is_err());
+        assert!(p.check("mailto:a@example.com").is_err());
+        let p = policy("", "other.org");
+        assert!(p.check("https://example.com").is_ok());
+        assert!(p.check("https://x.other.org").is_err());
+    }
+
+    #[test]
+    fn changing() {
+        let p = policy("", "other.org");
+        assert!(p
+            .check_change(Some("https://other.org"), "https://other.org")
+            .is_ok());
+        assert!(p
+            .check_change(Some("https://other.org"), "other.org")
+            .is_ok());
+        assert!(p
+            .check_change(Some("https://example.com"), "https://other.org")
+            .is_err());
+        assert!(p.check_change(None, "https://other.org").is_err());
+        assert!(p.check_change(None, "https://example.com").is_ok());
+    }
+
+    #[test]
+    fn violating() {
+        let links = [
+            utils::test_link("a", Some("https://example.com"), None),
+            utils::test_link("b", Some("https://other.org"), None),
+            utils::test_link("c", None, None),
+        ];
+        let found = violations(&policy("example.com", ""), &links);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].0.short.key.as_ref(), "b");
+    }
+}
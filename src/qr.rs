@@ -0,0 +1,496 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use dioxus::prelude::*;
+use qrcodegen::{QrCode, QrCodeEcc};
+
+use crate::{
+    form::FieldError,
+    query,
+    store::use_links,
+    utils::{self, Link},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Ecc {
+    Low,
+    Medium,
+    Quartile,
+    High,
+}
+
+impl Ecc {
+    pub const ALL: [Ecc; 4] = [Ecc::Low, Ecc::Medium, Ecc::Quartile, Ecc::High];
+
+    fn name(self) -> &'static str {
+        match self {
+            Ecc::Low => "Low (7%)",
+            Ecc::Medium => "Medium (15%)",
+            Ecc::Quartile => "Quartile (25%)",
+            Ecc::High => "High (30%)",
+        }
+    }
+}
+
+impl From<Ecc> for QrCodeEcc {
+    fn from(value: Ecc) -> Self {
+        match value {
+            Ecc::Low => QrCodeEcc::Low,
+            Ecc::Medium => QrCodeEcc::Medium,
+            Ecc::Quartile => QrCodeEcc::Quartile,
+            Ecc::High => QrCodeEcc::High,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct QrOptions {
+    // Requested width in pixels, rounded down to a whole number of pixels per module.
+    pub size: u32,
+    pub ecc: Ecc,
+    // Quiet zone in modules, the spec asks for 4.
+    pub margin: u32,
+    pub foreground: String,
+    pub background: String,
+}
+
+impl Default for QrOptions {
+    fn default() -> Self {
+        Self {
+            size: 256,
+            ecc: Ecc::Medium,
+            margin: 4,
+            foreground: String::from("#000000"),
+            background: String::from("#ffffff"),
+        }
+    }
+}
+
+pub fn parse_color(color: &str) -> Option<[u8; 3]> {
+    let hex = color.strip_prefix('#')?;
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+pub struct Qr {
+    code: QrCode,
+    options: QrOptions,
+    foreground: [u8; 3],
+    background: [u8; 3],
+}
+
+impl Qr {
+    pub fn new(text: &str, options: QrOptions) -> Result<Self, Box<str>> {
+        let foreground = parse_color(&options.foreground).ok_or("Invalid foreground colour")?;
+        let background = parse_color(&options.background).ok_or("Invalid background colour")?;
+        let code = QrCode::encode_text(text, options.ecc.into())
+            .map_err(|_| "The text is too long for a QR code")?;
+        Ok(Self {
+            code,
+            options,
+            foreground,
+            background,
+        })
+    }
+
+    fn modules(&self) -> u32 {
+        self.code.size() as u32 + 2 * self.options.margin
+    }
+
+    fn scale(&self) -> u32 {
+        (self.options.size / self.modules()).max(1)
+    }
+
+    fn dark(&self, x: u32, y: u32) -> bool {
+        let margin = self.options.margin as i32;
+        self.code.get_module(x as i32 - margin, y as i32 - margin)
+    }
+
+    pub fn svg(&self) -> String {
+        let modules = self.modules();
+        let size = modules * self.scale();
+        let mut path = String::new();
+        for y in 0..modules {
+            for x in 0..modules {
+                if self.dark(x, y) {
+                    path.push_str(&format!("M{},{}h1v1h-1z", x, y));
+                }
+            }
+        }
+        format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {m} {m}" width="{s}" height="{s}" shape-rendering="crispEdges"><rect width="{m}" height="{m}" fill="{bg}"/><path fill="{fg}" d="{path}"/></svg>"#,
+            m = modules,
+            s = size,
+            bg = self.options.background,
+            fg = self.options.foreground,
+            path = path,
+        )
+    }
+
+    /// A two-colour indexed PNG. The image data is stored without
+    /// compression, which at one bit per pixel stays small enough.
+    pub fn png(&self) -> Vec<u8> {
+        let scale = self.scale();
+        let size = self.modules() * scale;
+        let row_len = 1 + size.div_ceil(8) as usize;
+        let mut raw = Vec::with_capacity(row_len * size as usize);
+        for y in 0..size {
+            // Filter type "none".
+            raw.push(0);
+            let mut byte = 0u8;
+            for x in 0..size {
+                if self.dark(x / scale, y / scale) {
+                    byte |= 0x80 >> (x % 8);
+                }
+                if x % 8 == 7 {
+                    raw.push(byte);
+                    byte = 0;
+                }
+            }
+            if !size.is_multiple_of(8) {
+                raw.push(byte);
+            }
+        }
+
+        let mut ihdr = Vec::with_capacity(13);
+        ihdr.extend_from_slice(&size.to_be_bytes());
+        ihdr.extend_from_slice(&size.to_be_bytes());
+        // Bit depth 1, colour type 3 (indexed), default compression, filter and interlace.
+        ihdr.extend_from_slice(&[1, 3, 0, 0, 0]);
+        let mut plte = Vec::with_capacity(6);
+        plte.extend_from_slice(&self.background);
+        plte.extend_from_slice(&self.foreground);
+
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        write_chunk(&mut png, b"IHDR", &ihdr);
+        write_chunk(&mut png, b"PLTE", &plte);
+        write_chunk(&mut png, b"IDAT", &zlib_stored(&raw));
+        write_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+
+    pub fn svg_data_url(&self) -> String {
+        format!(
+            "data:image/svg+xml;charset=utf-8,{}",
+            query::encode(&self.svg())
+        )
+    }
+
+    pub fn png_data_url(&self) -> String {
+        format!("data:image/png;base64,{}", STANDARD.encode(self.png()))
+    }
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+    let mut blocks = data.chunks(u16::MAX as usize).peekable();
+    if blocks.peek().is_none() {
+        out.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        out.push(u8::from(blocks.peek().is_none()));
+        let len = block.len() as u16;
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(block);
+    }
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &b in data {
+        crc ^= u32::from(b);
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + u32::from(byte)) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+#[component]
+pub fn QrPanel(text: Box<str>, name: Box<str>) -> Element {
+    let mut options = use_signal(QrOptions::default);
+    let qr = Qr::new(&text, options());
+    rsx! {
+        div { class: "grid sm:grid-cols-2 mt-2",
+            span { class: "text-xl", "QR code" }
+            div {
+                match &qr {
+                    Ok(qr) => rsx! {
+                        img {
+                            class: "border border-gray-300",
+                            src: "{qr.svg_data_url()}",
+                            alt: "QR code for {text}",
+                            width: 160,
+                            height: 160,
+                        }
+                        div { class: "flex flex-wrap gap-1 mt-1",
+                            a {
+                                class: "border border-gray-300 hover:bg-gray-200 px-1",
+                                href: "{qr.svg_data_url()}",
+                                download: "{name}.svg",
+                                "Download SVG"
+                            }
+                            // Encoding the PNG is slow, so it only happens on demand.
+                            button {
+                                r#type: "button",
+                                class: "border border-gray-300 hover:bg-gray-200 px-1",
+                                cursor: "pointer",
+                                onclick: {
+                                    let (text, name) = (text.clone(), name.clone());
+                                    move |_| {
+                                        let Ok(qr) = Qr::new(&text, options()) else {
+                                            return;
+                                        };
+                                        if let Err(e) = utils::download(&format!("{}.png", name), &qr.png_data_url()) {
+                                            utils::alert(&format!("Failed to download the PNG.\n\nError: {}", e));
+                                        }
+                                    }
+                                },
+                                "Download PNG"
+                            }
+                        }
+                    },
+                    Err(e) => rsx! {
+                        FieldError { error: Some(e.clone()) }
+                    },
+                }
+                div { class: "grid grid-cols-2 gap-1 mt-1",
+                    span { "Size (px)" }
+                    input {
+                        class: "border border-gray-300 px-2",
+                        r#type: "number",
+                        min: 64,
+                        max: 2048,
+                        step: 32,
+                        value: "{options.read().size}",
+                        oninput: move |e| {
+                            if let Ok(size) = e.value().parse::<u32>() {
+                                options.write().size = size.clamp(64, 2048);
+                            }
+                        },
+                    }
+                    span { "Error correction" }
+                    select {
+                        class: "border border-gray-300 px-2",
+                        cursor: "pointer",
+                        onchange: move |e| {
+                            if let Some(ecc) = Ecc::ALL.into_iter().find(|l| l.name() == e.value()) {
+                                options.write().ecc = ecc;
+                            }
+                        },
+                        for ecc in Ecc::ALL {
+                            option {
+                                selected: options.read().ecc == ecc,
+                                value: "{ecc.name()}",
+                                "{ecc.name()}"
+                            }
+                        }
+                    }
+                    span { "Margin (modules)" }
+                    input {
+                        class: "border border-gray-300 px-2",
+                        r#type: "number",
+                        min: 0,
+                        max: 16,
+                        value: "{options.read().margin}",
+                        oninput: move |e| {
+                            if let Ok(margin) = e.value().parse::<u32>() {
+                                options.write().margin = margin.min(16);
+                            }
+                        },
+                    }
+                    span { "Foreground" }
+                    input {
+                        r#type: "color",
+                        cursor: "pointer",
+                        value: "{options.read().foreground}",
+                        oninput: move |e| options.write().foreground = e.value(),
+                    }
+                    span { "Background" }
+                    input {
+                        r#type: "color",
+                        cursor: "pointer",
+                        value: "{options.read().background}",
+                        oninput: move |e| options.write().background = e.value(),
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn QrSheet() -> Element {
+    let links = use_links();
+    let mut selected = use_signal(Vec::<Box<str>>::new);
+    let links = match links() {
+        Some(Ok(links)) => links,
+        Some(Err(e)) => return Err(e.into()),
+        None => {
+            return rsx! {
+                div { class: "mb-2 text-2xl", "Loading..." }
+            }
+        }
+    };
+    let chosen: Vec<Link> = links
+        .iter()
+        .filter(|l| selected.read().contains(&l.short.key))
+        .cloned()
+        .collect();
+    rsx! {
+        div {
+            div { class: "flex flex-col print:hidden",
+                h1 { class: "text-3xl mx-auto", "QR code sheet" }
+            }
+            div { class: "flex flex-col w-9/12 sm:w-2/3 mx-auto mt-2 print:hidden",
+                div { class: "flex flex-wrap gap-1",
+                    button {
+                        class: "border border-gray-300 hover:bg-gray-200 px-2",
+                        cursor: "pointer",
+                        onclick: {
+                            let links = links.clone();
+                            move |_| selected.set(links.iter().map(|l| l.short.key.clone()).collect())
+                        },
+                        "Select all"
+                    }
+                    button {
+                        class: "border border-gray-300 hover:bg-gray-200 px-2",
+                        cursor: "pointer",
+                        onclick: move |_| selected.set(Vec::new()),
+                        "Select none"
+                    }
+                    button {
+                        class: "border border-gray-300 hover:bg-gray-200 px-2",
+                        cursor: "pointer",
+                        onclick: |_| {
+                            if let Ok(window) = utils::window() {
+                                let _ = window.print();
+                            }
+                        },
+                        "Print"
+                    }
+                }
+                for link in links.iter() {
+                    label { class: "border-b border-gray-300 break-all", cursor: "pointer",
+                        input {
+                            r#type: "checkbox",
+                            checked: selected.read().contains(&link.short.key),
+                            onchange: {
+                                let key = link.short.key.clone();
+                                move |e: FormEvent| {
+                                    if e.checked() {
+                                        selected.write().push(key.clone());
+                                    } else {
+                                        selected.write().retain(|k| *k != key);
+                                    }
+                                }
+                            },
+                        }
+                        " {link.short.key}"
+                    }
+                }
+            }
+            div { class: "grid grid-cols-3 gap-4 mt-2",
+                for link in chosen {
+                    QrSheetItem { link }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn QrSheetItem(link: Link) -> Element {
+    let qr = Qr::new(&link.short.full, QrOptions::default());
+    rsx! {
+        div { class: "flex flex-col items-center break-inside-avoid",
+            if let Ok(qr) = qr {
+                img { src: "{qr.svg_data_url()}", width: 160, height: 160 }
+            }
+            p { class: "text-xl", "{link.short.key}" }
+            p { class: "text-sm break-all", "{link.short.full}" }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colors() {
+        assert_eq!(parse_color("#ff8000"), Some([255, 128, 0]));
+        assert_eq!(parse_color("ff8000"), None);
+        assert_eq!(parse_color("#ff80"), None);
+        assert_eq!(parse_color("#\"/><x"), None);
+    }
+
+    #[test]
+    fn checksums() {
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+
+    #[test]
+    fn svg() {
+        let qr = Qr::new("https://s.example/abc", QrOptions::default()).unwrap();
+        let svg = qr.svg();
+        // Version 2 is 25 modules wide, plus the quiet zone on both sides.
+        assert!(svg.contains(r#"viewBox="0 0 33 33""#));
+        assert!(svg.contains(r##"fill="#000000""##));
+        assert!(Qr::new(
+            "x",
+            QrOptions {
+                foreground: "red".into(),
+                ..Default::default()
+            }
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn png() {
+        let qr = Qr::new("https://s.example/abc", QrOptions::default()).unwrap();
+        let png = qr.png();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+        // 256 / 33 rounds down to 7 pixels per module.
+        assert_eq!(&png[16..24], &[0, 0, 0, 231, 0, 0, 0, 231]);
+        assert!(png.ends_with(&[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]));
+    }
+
+    #[test]
+    fn stored_blocks() {
+        let data = vec![7u8; 70_000];
+        let zlib = zlib_stored(&data);
+        // Header, two block headers, data and checksum.
+        assert_eq!(zlib.len(), 2 + 5 + 5 + data.len() + 4);
+        assert_eq!(zlib[2], 0);
+        assert_eq!(zlib[2 + 5 + 65535], 1);
+    }
+}
@@ -0,0 +1,477 @@
+use dioxus::prelude::*;
+
+use crate::{
+    settings::{use_settings, Campaign},
+    utils,
+};
+
+// Click and campaign identifiers added by ad networks and mail tools.
+pub const TRACKING_PARAMS: [&str; 12] = [
+    "fbclid", "gclid", "dclid", "gbraid", "wbraid", "msclkid", "yclid", "mc_cid", "mc_eid",
+    "igshid", "_ga", "_gl",
+];
+
+pub const UTM_PARAMS: [&str; 5] = [
+    "utm_source",
+    "utm_medium",
+    "utm_campaign",
+    "utm_term",
+    "utm_content",
+];
+
+// Common utm_source and utm_medium pairs.
+const SOURCE_PRESETS: [(&str, &str); 5] = [
+    ("newsletter", "email"),
+    ("twitter", "social"),
+    ("facebook", "social"),
+    ("linkedin", "social"),
+    ("google", "cpc"),
+];
+
+/// Percent-encodes `value` for use as a query parameter name or value.
+pub fn encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for b in value.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(b as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    encoded
+}
+
+/// Decodes a query parameter name or value, reading `+` as a space.
+pub fn decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(b)) => {
+                decoded.push(b);
+                i += 3;
+                continue;
+            }
+            (b'+', _) => decoded.push(b' '),
+            (b, _) => decoded.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// One URL per non-empty line of `values`, each with `name` set to that line.
+/// A value already in `url` is replaced rather than repeated.
+pub fn variant_urls(url: &str, name: &str, values: &str) -> Vec<String> {
+    values
+        .lines()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(|v| merge_params(url.trim(), &[(name.trim().into(), v.into())]))
+        .collect()
+}
+
+/// A decoded query parameter. Parameters read from a URL remember how they
+/// were written, so untouched ones are put back exactly as they were.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Param {
+    pub name: String,
+    pub value: String,
+    raw: Option<String>,
+}
+
+impl Param {
+    pub fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+            raw: None,
+        }
+    }
+
+    fn parse(raw: &str) -> Self {
+        let (name, value) = raw.split_once('=').unwrap_or((raw, ""));
+        Self {
+            name: decode(name),
+            value: decode(value),
+            raw: Some(raw.into()),
+        }
+    }
+
+    fn write(&self, url: &mut String) {
+        match &self.raw {
+            Some(raw) => url.push_str(raw),
+            None => {
+                url.push_str(&encode(&self.name));
+                url.push('=');
+                url.push_str(&encode(&self.value));
+            }
+        }
+    }
+}
+
+pub type Params = Vec<Param>;
+
+/// Splits `url` into the part before the query, the decoded parameters and
+/// the fragment.
+pub fn parse_query(url: &str) -> (&str, Params, Option<&str>) {
+    let (rest, fragment) = match url.split_once('#') {
+        Some((rest, fragment)) => (rest, Some(fragment)),
+        None => (url, None),
+    };
+    let (base, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let params = query
+        .split('&')
+        .filter(|p| !p.is_empty())
+        .map(Param::parse)
+        .collect();
+    (base, params, fragment)
+}
+
+pub fn with_params(base: &str, params: &[Param], fragment: Option<&str>) -> String {
+    let mut url = String::from(base);
+    for (i, param) in params.iter().enumerate() {
+        url.push(if i == 0 { '?' } else { '&' });
+        param.write(&mut url);
+    }
+    if let Some(fragment) = fragment {
+        url.push('#');
+        url.push_str(fragment);
+    }
+    url
+}
+
+/// Sets each of `params` on `url`, replacing parameters with the same name.
+pub fn merge_params(url: &str, params: &[(String, String)]) -> String {
+    let (base, mut current, fragment) = parse_query(url);
+    for (name, value) in params {
+        match current.iter_mut().find(|p| p.name == *name) {
+            Some(param) => *param = Param::new(name.clone(), value.clone()),
+            None => current.push(Param::new(name.clone(), value.clone())),
+        }
+    }
+    with_params(base, &current, fragment)
+}
+
+fn is_tracking(name: &str) -> bool {
+    name.starts_with("utm_") || TRACKING_PARAMS.contains(&name)
+}
+
+/// Removes UTM and click-tracking parameters. The URL is returned unchanged
+/// when it has none.
+pub fn strip_tracking(url: &str) -> String {
+    let (base, params, fragment) = parse_query(url);
+    if !params.iter().any(|p| is_tracking(&p.name)) {
+        return url.into();
+    }
+    let params: Params = params
+        .into_iter()
+        .filter(|p| !is_tracking(&p.name))
+        .collect();
+    with_params(base, &params, fragment)
+}
+
+#[component]
+pub fn QueryBuilder(url: String, onchange: EventHandler<String>) -> Element {
+    let mut settings = use_settings();
+    let mut template_name = use_signal(String::new);
+    // Values of rows without a name, kept here until they get one so the URL
+    // never gains a nameless `=value` pair.
+    let mut unnamed = use_signal(Vec::<String>::new);
+    let (base, params, fragment) = parse_query(&url);
+    let base = base.to_string();
+    let fragment = fragment.map(String::from);
+    let set = move |params: Params| onchange.call(with_params(&base, &params, fragment.as_deref()));
+    let tracked = params.iter().any(|p| is_tracking(&p.name));
+    let campaigns = settings.read().campaigns.clone();
+    // One list, so a row keeps its inputs, and the focus, once it is named.
+    let rows: Vec<Param> = params
+        .iter()
+        .cloned()
+        .chain(
+            unnamed
+                .read()
+                .iter()
+                .map(|value| Param::new("", value.clone())),
+        )
+        .collect();
+    rsx! {
+        details { class: "mt-1",
+            summary { cursor: "pointer", "Query parameters ({params.len()})" }
+            div { class: "flex flex-col gap-1 mt-1",
+                for (i , param) in rows.into_iter().enumerate() {
+                    div { class: "flex gap-1",
+                        input {
+                            class: "border border-gray-300 px-1 w-full",
+                            r#type: "text",
+                            placeholder: "Name",
+                            value: "{param.name}",
+                            oninput: {
+                                let (mut params, set) = (params.clone(), set.clone());
+                                move |e: FormEvent| {
+                                    let name = e.value();
+                                    if i < params.len() {
+                                        if name.is_empty() {
+                                            let cleared = params.remove(i);
+                                            unnamed.write().insert(0, cleared.value);
+                                        } else {
+                                            params[i] = Param::new(name, params[i].value.clone());
+                                        }
+                                        set(params.clone());
+                                    } else if !name.is_empty() {
+                                        let value = unnamed.write().remove(i - params.len());
+                                        params.push(Param::new(name, value));
+                                        set(params.clone());
+                                    }
+                                }
+                            },
+                        }
+                        input {
+                            class: "border border-gray-300 px-1 w-full",
+                            r#type: "text",
+                            placeholder: "Value",
+                            value: "{param.value}",
+                            oninput: {
+                                let (mut params, set) = (params.clone(), set.clone());
+                                move |e: FormEvent| {
+                                    if i < params.len() {
+                                        params[i] = Param::new(params[i].name.clone(), e.value());
+                                        set(params.clone());
+                                    } else {
+                                        unnamed.write()[i - params.len()] = e.value();
+                                    }
+                                }
+                            },
+                        }
+                        button {
+                            r#type: "button",
+                            class: "border border-gray-300 hover:bg-gray-200 px-1",
+                            cursor: "pointer",
+                            title: "Remove",
+                            onclick: {
+                                let (mut params, set) = (params.clone(), set.clone());
+                                move |_| {
+                                    if i < params.len() {
+                                        params.remove(i);
+                                        set(params.clone());
+                                    } else {
+                                        unnamed.write().remove(i - params.len());
+                                    }
+                                }
+                            },
+                            "×"
+                        }
+                    }
+                }
+                div { class: "flex flex-wrap gap-1",
+                    button {
+                        r#type: "button",
+                        class: "border border-gray-300 hover:bg-gray-200 px-1",
+                        cursor: "pointer",
+                        onclick: move |_| unnamed.write().push(String::new()),
+                        "Add parameter"
+                    }
+                    button {
+                        r#type: "button",
+                        class: "border border-gray-300 hover:bg-gray-200 px-1",
+                        cursor: "pointer",
+                        onclick: {
+                            let (mut params, set) = (params.clone(), set.clone());
+                            move |_| {
+                                for name in UTM_PARAMS.into_iter().take(3) {
+                                    if !params.iter().any(|p| p.name == name) {
+                                        params.push(Param::new(name, ""));
+                                    }
+                                }
+                                set(params.clone());
+                            }
+                        },
+                        "Add UTM fields"
+                    }
+                    if tracked {
+                        button {
+                            r#type: "button",
+                            class: "border border-gray-300 hover:bg-gray-200 px-1",
+                            cursor: "pointer",
+                            onclick: {
+                                let url = url.clone();
+                                move |_| onchange.call(strip_tracking(&url))
+                            },
+                            "Strip tracking parameters"
+                        }
+                    }
+                }
+                div { class: "flex flex-wrap gap-1 text-sm",
+                    for (source , medium) in SOURCE_PRESETS {
+                        button {
+                            r#type: "button",
+                            class: "border border-gray-300 hover:bg-gray-200 px-1",
+                            cursor: "pointer",
+                            onclick: {
+                                let url = url.clone();
+                                move |_| {
+                                    let preset = [
+                                        ("utm_source".into(), source.into()),
+                                        ("utm_medium".into(), medium.into()),
+                                    ];
+                                    onchange.call(merge_params(&url, &preset))
+                                }
+                            },
+                            "{source} / {medium}"
+                        }
+                    }
+                }
+                if !campaigns.is_empty() {
+                    div { class: "flex flex-wrap gap-1 text-sm",
+                        span { "Templates:" }
+                        for campaign in campaigns {
+                            button {
+                                r#type: "button",
+                                class: "border border-gray-300 hover:bg-gray-200 px-1",
+                                cursor: "pointer",
+                                onclick: {
+                                    let (url, params) = (url.clone(), campaign.params.clone());
+                                    move |_| onchange.call(merge_params(&url, &params))
+                                },
+                                "{campaign.name}"
+                            }
+                        }
+                    }
+                }
+                div { class: "flex gap-1",
+                    input {
+                        class: "border border-gray-300 px-1 w-full",
+                        r#type: "text",
+                        placeholder: "Template name",
+                        value: "{template_name}",
+                        oninput: move |e| template_name.set(e.value()),
+                    }
+                    button {
+                        r#type: "button",
+                        class: "border border-gray-300 hover:bg-gray-200 px-1 whitespace-nowrap",
+                        cursor: "pointer",
+                        disabled: template_name.read().trim().is_empty() || params.is_empty(),
+                        onclick: {
+                            let params: Vec<(String, String)> = params
+                                .iter()
+                                .map(|p| (p.name.clone(), p.value.clone()))
+                                .collect();
+                            move |_| {
+                                let mut new = settings.read().clone();
+                                new.save_campaign(Campaign {
+                                    name: template_name.read().trim().into(),
+                                    params: params.clone(),
+                                });
+                                match new.save() {
+                                    Ok(()) => {
+                                        settings.set(new);
+                                        template_name.set(String::new());
+                                    }
+                                    Err(e) => utils::alert(&format!("Failed to save the template.\n\nError: {}", e)),
+                                }
+                            }
+                        },
+                        "Save as template"
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encoding() {
+        assert_eq!(encode("spring sale"), "spring%20sale");
+        assert_eq!(encode("a&b=c"), "a%26b%3Dc");
+        assert_eq!(encode("ü"), "%C3%BC");
+        assert_eq!(encode("x-y_z.~"), "x-y_z.~");
+    }
+
+    #[test]
+    fn decoding() {
+        assert_eq!(decode("spring%20sale"), "spring sale");
+        assert_eq!(decode("a+b"), "a b");
+        assert_eq!(decode("%C3%BC"), "ü");
+        assert_eq!(decode("100%"), "100%");
+        assert_eq!(decode("%zz"), "%zz");
+    }
+
+    #[test]
+    fn variants() {
+        assert_eq!(
+            variant_urls("https://a.b", "utm_source", "twitter\n\n mail \n"),
+            [
+                "https://a.b?utm_source=twitter",
+                "https://a.b?utm_source=mail"
+            ]
+        );
+        assert_eq!(
+            variant_urls("https://a.b/?utm_source=x&k=1#top", "utm_source", "mail"),
+            ["https://a.b/?utm_source=mail&k=1#top"]
+        );
+    }
+
+    #[test]
+    fn query() {
+        let (base, mut params, fragment) = parse_query("https://a.b/p?x=1&y=a+b&flag#top");
+        assert_eq!(base, "https://a.b/p");
+        let decoded: Vec<(&str, &str)> = params
+            .iter()
+            .map(|p| (p.name.as_str(), p.value.as_str()))
+            .collect();
+        assert_eq!(decoded, [("x", "1"), ("y", "a b"), ("flag", "")]);
+        assert_eq!(fragment, Some("top"));
+        assert_eq!(
+            with_params(base, &params, fragment),
+            "https://a.b/p?x=1&y=a+b&flag#top"
+        );
+        params[0] = Param::new("x", "2 3");
+        assert_eq!(
+            with_params(base, &params, fragment),
+            "https://a.b/p?x=2%203&y=a+b&flag#top"
+        );
+        assert_eq!(with_params("https://a.b", &[], None), "https://a.b");
+    }
+
+    #[test]
+    fn merging() {
+        assert_eq!(
+            merge_params(
+                "https://a.b?utm_source=x&k=1",
+                &[
+                    ("utm_source".into(), "mail".into()),
+                    ("utm_medium".into(), "email".into())
+                ]
+            ),
+            "https://a.b?utm_source=mail&k=1&utm_medium=email"
+        );
+        assert_eq!(
+            merge_params("https://a.b?q=a+b&flag", &[("k".into(), "v".into())]),
+            "https://a.b?q=a+b&flag&k=v"
+        );
+    }
+
+    #[test]
+    fn stripping() {
+        assert_eq!(
+            strip_tracking("https://a.b/?id=3&utm_source=x&fbclid=abc#f"),
+            "https://a.b/?id=3#f"
+        );
+        assert_eq!(strip_tracking("https://a.b/?q=a+b"), "https://a.b/?q=a+b");
+        assert_eq!(strip_tracking("https://a.b/?gclid=1"), "https://a.b/");
+        assert_eq!(
+            strip_tracking("https://a.b/?q=a+b&flag&utm_source=x"),
+            "https://a.b/?q=a+b&flag"
+        );
+    }
+}
@@ -0,0 +1,272 @@
+use dioxus::prelude::*;
+
+use crate::{
+    form::{self, FieldError, MIN_EXPIRATION_TTL},
+    history::{self, Removal},
+    meta::use_metadata,
+    policy::{use_policy, Policy},
+    store::use_links,
+    utils::{self, CreateRequestBody, Link},
+    Route,
+};
+
+/// The request that recreates `link` under `new_key`, keeping its
+/// destination and what is left of its expiration.
+pub fn rename_body(
+    link: &Link,
+    new_key: &str,
+    policy: &Policy,
+    now: i64,
+) -> Result<CreateRequestBody, Box<str>> {
+    let short = form::parse_key(new_key)?.ok_or("New key is required")?;
+    if short == link.short.key {
+        return Err("New key is the same as the current one".into());
+    }
+    let url = link
+        .url
+        .clone()
+        .ok_or("The link has no destination to carry over")?;
+    // The new key points nowhere yet.
+    policy.check_change(None, &url)?;
+    if let Some(expiration) = link.expiration {
+        if expiration < now + i64::from(MIN_EXPIRATION_TTL) {
+            return Err("The link expires too soon to be renamed".into());
+        }
+    }
+    Ok(CreateRequestBody {
+        url,
+        short: Some(short),
+        length: None,
+        number: None,
+        capital: None,
+        lowercase: None,
+        expiration: link.expiration,
+        expiration_ttl: None,
+    })
+}
+
+/// Checks that the backend created exactly the requested key and that it
+/// still points where the old link did.
+pub fn verify(created: &Link, requested: &str, fetched: &[Link]) -> Result<(), String> {
+    if *created.short.key != *requested {
+        return Err(format!(
+            "The backend created {} instead of {}.",
+            created.short.key, requested
+        ));
+    }
+    let found = fetched.iter().any(|l| {
+        l.short.key == created.short.key
+            && l.url == created.url
+            && l.expiration == created.expiration
+    });
+    if found {
+        Ok(())
+    } else {
+        Err(format!("The new key {} could not be verified.", requested))
+    }
+}
+
+/// Creates the new key, checks that it points where the old one did, then
+/// deletes the old key. The new key is removed again if a later step fails.
+pub async fn rename_link(
+    link: &Link,
+    new_key: &str,
+    policy: &Policy,
+) -> Result<Box<str>, Box<str>> {
+    let body = rename_body(link, new_key, policy, utils::now())?;
+    let requested = body.short.clone().unwrap_or_default();
+    let url = body.url.clone();
+    let expiration = body.expiration;
+    let created = Link {
+        short: history::create_link(body)
+            .await
+            .map_err(|e| format!("Failed to create the new key.\n\nError: {}", e))?,
+        url: Some(url),
+        expiration,
+    };
+    let short = created.short.key.clone();
+    let verified = match utils::fetch_links().await {
+        Ok(links) => verify(&created, &requested, &links),
+        Err(e) => Err(format!(
+            "The new key {} could not be verified.\n\nError: {}",
+            requested, e
+        )),
+    };
+    let failure = match verified {
+        Err(e) => e,
+        Ok(()) => match history::delete_link(link, Removal::Renamed).await {
+            Ok(()) => return Ok(short),
+            Err(e) => format!("Failed to delete the old key.\n\nError: {}", e),
+        },
+    };
+    match history::delete_link(&created, Removal::Stray).await {
+        Ok(()) => Err(format!(
+            "{}\n\n{} was removed again, nothing changed.",
+            failure, short
+        )
+        .into()),
+        Err(e) => Err(format!(
+            "{}\n\nRolling back also failed, both {} and {} may now exist.\n\nError: {}",
+            failure, link.short.key, short, e
+        )
+        .into()),
+    }
+}
+
+#[component]
+pub fn RenameKey(link: Link) -> Element {
+    let mut links = use_links();
+    let mut new_key = use_signal(String::new);
+    let mut renaming = use_signal(|| false);
+    let policy = use_policy();
+    let mut metadata = use_metadata();
+    let taken = match &*links.read() {
+        Some(Ok(links)) => form::key_taken(&new_key.read(), links),
+        _ => false,
+    };
+    let error = if new_key.read().trim().is_empty() {
+        None
+    } else if taken {
+        Some("This key is already taken".into())
+    } else {
+        rename_body(&link, &new_key.read(), &policy.read(), utils::now()).err()
+    };
+    let disabled = renaming() || error.is_some() || new_key.read().trim().is_empty();
+    rsx! {
+        div { class: "grid sm:grid-cols-2 mt-2",
+            span { class: "text-xl", "Rename key" }
+            div {
+                input {
+                    class: "border border-gray-300 px-2",
+                    r#type: "text",
+                    name: "newKey",
+                    placeholder: "New key",
+                    maxlength: form::MAX_KEY_LENGTH as i64,
+                    value: "{new_key}",
+                    oninput: move |e| new_key.set(e.value()),
+                }
+                button {
+                    r#type: "button",
+                    class: "border border-gray-300 hover:bg-gray-200 px-2 ml-1",
+                    cursor: "pointer",
+                    disabled,
+                    onclick: move |_| {
+                        let link = link.clone();
+                        async move {
+                            let to = new_key.read().trim().to_string();
+                            if !utils::confirm(
+                                &format!("Move {} to {}? The old key will stop working.", link.short.key, to),
+                            ) {
+                                return;
+                            }
+                            renaming.set(true);
+                            let policy = policy.read().clone();
+                            let result = rename_link(&link, &to, &policy).await;
+                            renaming.set(false);
+                            links.restart();
+                            match result {
+                                Ok(short) => {
+                                    let mut moved = metadata.read().clone();
+                                    moved.rename(&link.short.key, &short);
+                                    if let Err(e) = moved.save() {
+                                        utils::alert(
+                                            &format!("Failed to move the notes to {}.\n\nError: {}", short, e),
+                                        );
+                                    }
+                                    metadata.set(moved);
+                                    utils::alert(&format!("Link renamed to {}", short));
+                                    use_navigator()
+                                        .replace(Route::LinkItem {
+                                            link: short.into(),
+                                        });
+                                }
+                                Err(e) => utils::alert(&e),
+                            }
+                        }
+                    },
+                    if renaming() {
+                        "Renaming..."
+                    } else {
+                        "Rename"
+                    }
+                }
+                FieldError { error }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_750_000_000;
+
+    fn link(url: Option<&str>, expiration: Option<i64>) -> Link {
+        utils::test_link("old", url, expiration)
+    }
+
+    #[test]
+    fn leaves_the_trash_alone() {
+        // The old key lives on under the new one and a rolled back key was never
+        // wanted, so neither may be restored as a second copy.
+        assert!(!Removal::Renamed.keeps_in_trash());
+        assert!(!Removal::Stray.keeps_in_trash());
+        assert!(Removal::Deleted.keeps_in_trash());
+    }
+
+    #[test]
+    fn body() {
+        let body = rename_body(
+            &link(Some("https://a.b"), Some(NOW + 3600)),
+            "new",
+            &Policy::default(),
+            NOW,
+        )
+        .unwrap();
+        assert_eq!(body.short.as_deref(), Some("new"));
+        assert_eq!(body.url.as_ref(), "https://a.b");
+        assert_eq!(body.expiration, Some(NOW + 3600));
+        assert_eq!(body.expiration_ttl, None);
+        let body = rename_body(
+            &link(Some("https://a.b"), None),
+            "new",
+            &Policy::default(),
+            NOW,
+        )
+        .unwrap();
+        assert_eq!(body.expiration, None);
+    }
+
+    #[test]
+    fn refused() {
+        let ok = link(Some("https://a.b"), None);
+        assert!(rename_body(&ok, "", &Policy::default(), NOW).is_err());
+        assert!(rename_body(&ok, "old", &Policy::default(), NOW).is_err());
+        assert!(rename_body(&ok, "not valid", &Policy::default(), NOW).is_err());
+        assert!(rename_body(&link(None, None), "new", &Policy::default(), NOW).is_err());
+        assert!(rename_body(
+            &link(Some("https://a.b"), Some(NOW + 10)),
+            "new",
+            &Policy::default(),
+            NOW
+        )
+        .is_err());
+        let blocked = Policy {
+            blocked: vec!["a.b".into()],
+            ..Default::default()
+        };
+        assert!(rename_body(&ok, "new", &blocked, NOW).is_err());
+    }
+
+    #[test]
+    fn verifying() {
+        let created = utils::test_link("new", Some("https://a.b"), Some(NOW + 3600));
+        assert!(verify(&created, "new", std::slice::from_ref(&created)).is_ok());
+        let random = utils::test_link("x7Qa", Some("https://a.b"), Some(NOW + 3600));
+        assert!(verify(&random, "new", std::slice::from_ref(&random)).is_err());
+        assert!(verify(&created, "new", &[]).is_err());
+        let permanent = utils::test_link("new", Some("https://a.b"), None);
+        assert!(verify(&created, "new", &[permanent]).is_err());
+    }
+}
@@ -0,0 +1,219 @@
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    duration, error::Result, form::FieldError, meta::MetaTransfer, utils, views::ViewsPanel,
+};
+
+const STORAGE_KEY: &str = "settings";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub ttl_presets: Vec<Box<str>>,
+    pub campaigns: Vec<Campaign>,
+    // Remove tracking parameters from URLs pasted into the URL fields.
+    pub strip_tracking: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            ttl_presets: ["1h", "1d", "1w", "30d", "1y"].map(Box::from).into(),
+            campaigns: Vec::new(),
+            strip_tracking: false,
+        }
+    }
+}
+
+/// A named set of query parameters applied together.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Campaign {
+    pub name: Box<str>,
+    pub params: Vec<(String, String)>,
+}
+
+impl Settings {
+    pub fn load() -> Self {
+        utils::load(STORAGE_KEY).ok().flatten().unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        utils::save(STORAGE_KEY, self)
+    }
+
+    /// Adds `campaign`, replacing a template with the same name.
+    pub fn save_campaign(&mut self, campaign: Campaign) {
+        match self.campaigns.iter_mut().find(|c| c.name == campaign.name) {
+            Some(existing) => *existing = campaign,
+            None => self.campaigns.push(campaign),
+        }
+    }
+}
+
+pub fn use_settings() -> Signal<Settings> {
+    use_context()
+}
+
+fn parse_presets(presets: &str) -> std::result::Result<Vec<Box<str>>, Box<str>> {
+    presets
+        .lines()
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(|p| {
+            duration::parse_duration(p)
+                .map(|_| Box::from(p))
+                .map_err(|e| format!("{}: {}", p, e).into())
+        })
+        .collect()
+}
+
+#[component]
+pub fn SettingsPage() -> Element {
+    let mut settings = use_settings();
+    let mut presets = use_signal(|| settings.read().ttl_presets.join("\n"));
+    let mut strip_tracking = use_signal(|| settings.read().strip_tracking);
+    let mut campaigns = use_signal(|| settings.read().campaigns.clone());
+    let presets_error = parse_presets(&presets.read()).err();
+    rsx! {
+        div {
+            div { class: "flex flex-col",
+                h1 { class: "text-3xl mx-auto", "Settings" }
+            }
+            form {
+                class: "mt-5",
+                onsubmit: move |_| {
+                    let Ok(ttl_presets) = parse_presets(&presets.read()) else {
+                        return;
+                    };
+                    let mut new = settings.read().clone();
+                    new.ttl_presets = ttl_presets;
+                    new.strip_tracking = strip_tracking();
+                    new.campaigns = campaigns();
+                    match new.save() {
+                        Ok(()) => {
+                            settings.set(new);
+                            utils::alert("Settings saved");
+                        }
+                        Err(e) => utils::alert(&format!("Failed to save the settings.\n\nError: {}", e)),
+                    }
+                },
+                div { class: "flex flex-col w-9/12 sm:w-2/3 mx-auto",
+                    div { class: "grid sm:grid-cols-2",
+                        div {
+                            p { class: "text-xl", "ExpirationTTL presets" }
+                            p { class: "text-sm text-gray-500", "One duration per line, e.g. 90m, 7d or 2w 3d" }
+                        }
+                        div {
+                            textarea {
+                                class: "border border-gray-300 px-2",
+                                rows: 6,
+                                resize: "none",
+                                name: "ttlPresets",
+                                value: "{presets}",
+                                oninput: move |e| presets.set(e.value()),
+                            }
+                            FieldError { error: presets_error }
+                        }
+                        div {
+                            p { class: "text-xl", "Strip tracking" }
+                            p { class: "text-sm text-gray-500", "Remove utm_* and click IDs from pasted URLs" }
+                        }
+                        div {
+                            input {
+                                r#type: "checkbox",
+                                name: "stripTracking",
+                                cursor: "pointer",
+                                checked: strip_tracking(),
+                                onchange: move |e| strip_tracking.set(e.checked()),
+                            }
+                        }
+                        div {
+                            p { class: "text-xl", "Campaign templates" }
+                            p { class: "text-sm text-gray-500", "Saved from the query parameter editor" }
+                        }
+                        div {
+                            if campaigns.read().is_empty() {
+                                p { class: "text-gray-500", "None yet." }
+                            }
+                            for (i , campaign) in campaigns.read().iter().enumerate() {
+                                div { class: "flex gap-1 items-start",
+                                    span { class: "flex-1 break-all",
+                                        "{campaign.name}: "
+                                        span { class: "text-sm text-gray-500",
+                                            {
+                                                campaign
+                                                    .params
+                                                    .iter()
+                                                    .map(|(n, v)| format!("{}={}", n, v))
+                                                    .collect::<Vec<_>>()
+                                                    .join(", ")
+                                            }
+                                        }
+                                    }
+                                    button {
+                                        r#type: "button",
+                                        class: "border border-gray-300 hover:bg-gray-200 px-1",
+                                        cursor: "pointer",
+                                        onclick: move |_| {
+                                            campaigns.write().remove(i);
+                                        },
+                                        "Remove"
+                                    }
+                                }
+                            }
+                        }
+                        div {
+                            p { class: "text-xl", "Link notes" }
+                            p { class: "text-sm text-gray-500",
+                                "Tags, notes and stars of this backend's links, saved immediately on import"
+                            }
+                        }
+                        div { MetaTransfer {} }
+                        div {
+                            p { class: "text-xl", "Saved views" }
+                            p { class: "text-sm text-gray-500", "List filters saved for this backend" }
+                        }
+                        div { ViewsPanel {} }
+                    }
+                    div { class: "mt-1 mx-auto",
+                        button {
+                            class: "border border-gray-300 hover:bg-gray-200 px-2 text-2xl",
+                            cursor: "pointer",
+                            "Save"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn campaigns() {
+        let mut settings = Settings::default();
+        let campaign = |name: &str, value: &str| Campaign {
+            name: name.into(),
+            params: vec![("utm_campaign".into(), value.into())],
+        };
+        settings.save_campaign(campaign("spring", "a"));
+        settings.save_campaign(campaign("fall", "b"));
+        settings.save_campaign(campaign("spring", "c"));
+        assert_eq!(
+            settings.campaigns,
+            [campaign("spring", "c"), campaign("fall", "b")]
+        );
+    }
+
+    #[test]
+    fn old_settings() {
+        let settings: Settings = serde_json::from_str(r#"{"ttl_presets":["2h"]}"#).unwrap();
+        assert_eq!(settings.ttl_presets, [Box::from("2h")]);
+        assert!(settings.campaigns.is_empty());
+        assert!(!settings.strip_tracking);
+    }
+}
@@ -0,0 +1,254 @@
+use dioxus::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    js_sys::{Function, Object, Reflect},
+    wasm_bindgen::{JsCast, JsValue},
+};
+
+use crate::{
+    error::{Error, Result},
+    utils::{self, Short},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Full,
+    NoHttps,
+    Markdown,
+    Html,
+    Rst,
+}
+
+impl Format {
+    pub const ALL: [Format; 5] = [
+        Format::Full,
+        Format::NoHttps,
+        Format::Markdown,
+        Format::Html,
+        Format::Rst,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Format::Full => "URL",
+            Format::NoHttps => "Without https://",
+            Format::Markdown => "Markdown",
+            Format::Html => "HTML",
+            Format::Rst => "reStructuredText",
+        }
+    }
+
+    pub fn render(self, short: &Short) -> String {
+        match self {
+            Format::Full => short.full.to_string(),
+            Format::NoHttps => short.no_https.to_string(),
+            Format::Markdown => format!(
+                "[{}]({})",
+                escape_markdown(&short.no_https),
+                short.full.replace('(', "%28").replace(')', "%29")
+            ),
+            Format::Html => format!(
+                r#"<a href="{}">{}</a>"#,
+                escape_html(&short.full),
+                escape_html(&short.no_https)
+            ),
+            Format::Rst => format!(
+                "`{} <{}>`_",
+                short.no_https.replace('`', "\\`").replace('<', "\\<"),
+                short.full
+            ),
+        }
+    }
+}
+
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '[' | ']' | '*' | '_' | '`') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub async fn copy(text: &str) -> Result<()> {
+    let clipboard = utils::window()?.navigator().clipboard();
+    JsFuture::from(clipboard.write_text(text)).await?;
+    Ok(())
+}
+
+// `navigator.share` is still behind web-sys' unstable APIs, so it is looked up by hand.
+fn share_function() -> Option<(web_sys::Navigator, Function)> {
+    let navigator = utils::window().ok()?.navigator();
+    let share = Reflect::get(&navigator, &JsValue::from_str("share"))
+        .ok()?
+        .dyn_into::<Function>()
+        .ok()?;
+    Some((navigator, share))
+}
+
+pub fn can_share() -> bool {
+    share_function().is_some()
+}
+
+pub async fn share(title: &str, url: &str) -> Result<()> {
+    let (navigator, share) =
+        share_function().ok_or(Error::Js("Sharing is not supported".into()))?;
+    let data = Object::new();
+    Reflect::set(
+        &data,
+        &JsValue::from_str("title"),
+        &JsValue::from_str(title),
+    )?;
+    Reflect::set(&data, &JsValue::from_str("url"), &JsValue::from_str(url))?;
+    let promise = share.call1(&navigator, &data)?;
+    JsFuture::from(web_sys::js_sys::Promise::from(promise)).await?;
+    Ok(())
+}
+
+#[component]
+pub fn CopyButton(text: String, label: Option<String>) -> Element {
+    let mut copied = use_signal(|| false);
+    let label = label.unwrap_or_else(|| String::from("Copy"));
+    rsx! {
+        button {
+            r#type: "button",
+            class: "border border-gray-300 hover:bg-gray-200 px-2",
+            cursor: "pointer",
+            onclick: move |_| {
+                let text = text.clone();
+                async move {
+                    match copy(&text).await {
+                        Ok(()) => {
+                            copied.set(true);
+                            utils::sleep(2000).await;
+                            copied.set(false);
+                        }
+                        Err(e) => utils::alert(&format!("Failed to copy.\n\nError: {}", e)),
+                    }
+                }
+            },
+            if copied() {
+                "Copied!"
+            } else {
+                "{label}"
+            }
+        }
+    }
+}
+
+/// A compact copy button with a choice of format, for rows in a list.
+#[component]
+pub fn CopyMenu(short: Short) -> Element {
+    let mut format = use_signal(|| Format::Full);
+    rsx! {
+        div { class: "flex gap-1",
+            select {
+                class: "border border-gray-300",
+                cursor: "pointer",
+                title: "Format to copy",
+                onchange: move |e| {
+                    if let Some(f) = Format::ALL.into_iter().find(|f| f.name() == e.value()) {
+                        format.set(f);
+                    }
+                },
+                for f in Format::ALL {
+                    option { value: f.name(), selected: format() == f, "{f.name()}" }
+                }
+            }
+            CopyButton { text: format().render(&short) }
+        }
+    }
+}
+
+#[component]
+pub fn SharePanel(short: Short) -> Element {
+    rsx! {
+        div { class: "grid sm:grid-cols-2 mt-2",
+            span { class: "text-xl", "Share" }
+            div { class: "flex flex-col gap-1",
+                for format in Format::ALL {
+                    div { class: "flex gap-1",
+                        input {
+                            class: "border border-gray-300 px-2 flex-1",
+                            r#type: "text",
+                            readonly: true,
+                            title: format.name(),
+                            value: format.render(&short),
+                        }
+                        CopyButton {
+                            key: "{format.name()}",
+                            text: format.render(&short),
+                            label: format!("Copy {}", format.name()),
+                        }
+                    }
+                }
+                if can_share() {
+                    div {
+                        button {
+                            r#type: "button",
+                            class: "border border-gray-300 hover:bg-gray-200 px-2",
+                            cursor: "pointer",
+                            onclick: {
+                                let short = short.clone();
+                                move |_| {
+                                    let short = short.clone();
+                                    async move {
+                                        // Closing the share sheet rejects the promise, which is not worth an alert.
+                                        let _ = share(&short.key, &short.full).await;
+                                    }
+                                }
+                            },
+                            "Share..."
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn short() -> Short {
+        utils::test_link("abc", None, None).short
+    }
+
+    #[test]
+    fn formats() {
+        let short = short();
+        assert_eq!(Format::Full.render(&short), "https://s.example/abc");
+        assert_eq!(Format::NoHttps.render(&short), "s.example/abc");
+        assert_eq!(
+            Format::Markdown.render(&short),
+            "[s.example/abc](https://s.example/abc)"
+        );
+        assert_eq!(
+            Format::Html.render(&short),
+            r#"<a href="https://s.example/abc">s.example/abc</a>"#
+        );
+        assert_eq!(
+            Format::Rst.render(&short),
+            "`s.example/abc <https://s.example/abc>`_"
+        );
+    }
+
+    #[test]
+    fn escaping() {
+        assert_eq!(escape_markdown("a_b[1]"), r"a\_b\[1\]");
+        assert_eq!(
+            escape_html(r#"<a href="x">&"#),
+            "&lt;a href=&quot;x&quot;&gt;&amp;"
+        );
+    }
+}
@@ -0,0 +1,20 @@
+use dioxus::prelude::*;
+
+use crate::{
+    error::Result,
+    utils::{self, Link},
+};
+
+pub type Links = Resource<Result<Box<[Link]>>>;
+
+/// Fetches the link list once for every page below the caller. Call
+/// `restart` on the returned resource after changing links.
+pub fn use_links_provider() -> Links {
+    #[allow(clippy::redundant_closure)]
+    let links = use_resource(|| utils::fetch_links());
+    use_context_provider(|| links)
+}
+
+pub fn use_links() -> Links {
+    use_context()
+}
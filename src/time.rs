@@ -0,0 +1,272 @@
+use std::{cell::RefCell, collections::HashMap};
+
+use chrono::{DateTime, Datelike, Days, Months, NaiveDate, NaiveDateTime, NaiveTime};
+use dioxus::prelude::*;
+use web_sys::{
+    js_sys::{Array, Date, Function, Intl, Object, Reflect},
+    wasm_bindgen::{JsCast, JsValue},
+};
+
+pub const UTC: &str = "UTC";
+
+// What `<input type="datetime-local">` produces, with and without seconds.
+const DATETIME_LOCAL_FORMATS: [&str; 2] = ["%Y-%m-%dT%H:%M", "%Y-%m-%dT%H:%M:%S"];
+
+pub fn parse_datetime_local(value: &str) -> Option<NaiveDateTime> {
+    DATETIME_LOCAL_FORMATS
+        .iter()
+        .find_map(|f| NaiveDateTime::parse_from_str(value, f).ok())
+}
+
+pub fn to_datetime_local(timestamp: i64, offset: i32) -> String {
+    DateTime::from_timestamp(timestamp + i64::from(offset), 0)
+        .map(|d| d.naive_utc().format(DATETIME_LOCAL_FORMATS[0]).to_string())
+        .unwrap_or_default()
+}
+
+/// Converts a wall clock time to a timestamp, given the UTC offset of the
+/// time zone at any instant.
+///
+/// The offset is looked up twice so that a wall clock time next to a DST
+/// transition still lands on the offset that applies to the result.
+pub fn to_timestamp(local: NaiveDateTime, offset: impl Fn(i64) -> i32) -> i64 {
+    let guess = local.and_utc().timestamp();
+    let first = guess - i64::from(offset(guess));
+    guess - i64::from(offset(first))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Period {
+    Day,
+    Week,
+    Month,
+}
+
+/// The first instant after the local day, ISO week or month containing
+/// `timestamp`, assuming the offset stays the same until then.
+pub fn end_of(period: Period, timestamp: i64, offset: i32) -> i64 {
+    let Some(local) = DateTime::from_timestamp(timestamp + i64::from(offset), 0) else {
+        return timestamp;
+    };
+    let date = local.date_naive();
+    let next = match period {
+        Period::Day => date.succ_opt(),
+        Period::Week => date.checked_add_days(Days::new(
+            7 - u64::from(date.weekday().num_days_from_monday()),
+        )),
+        Period::Month => date
+            .with_day(1)
+            .and_then(|d| d.checked_add_months(Months::new(1))),
+    };
+    next.map(|d| d.and_time(NaiveTime::MIN).and_utc().timestamp() - i64::from(offset))
+        .unwrap_or(timestamp)
+}
+
+pub fn format_offset(offset: i32) -> String {
+    let sign = if offset < 0 { '-' } else { '+' };
+    let offset = offset.unsigned_abs();
+    format!("{}{:02}:{:02}", sign, offset / 3600, offset % 3600 / 60)
+}
+
+pub fn format_local(timestamp: i64, offset: i32) -> String {
+    DateTime::from_timestamp(timestamp + i64::from(offset), 0)
+        .map(|d| {
+            format!(
+                "{} ({})",
+                d.naive_utc().format("%Y-%m-%d %H:%M:%S"),
+                format_offset(offset)
+            )
+        })
+        .unwrap_or_default()
+}
+
+pub fn format_utc(timestamp: i64) -> String {
+    DateTime::from_timestamp(timestamp, 0)
+        .map(|d| d.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+        .unwrap_or_default()
+}
+
+fn intl_options(entries: &[(&str, &str)]) -> Object {
+    let options = Object::new();
+    for (key, value) in entries {
+        let _ = Reflect::set(&options, &JsValue::from_str(key), &JsValue::from_str(value));
+    }
+    options
+}
+
+thread_local! {
+    // Building an `Intl.DateTimeFormat` is slow, and both are needed for every
+    // expiration shown.
+    static BROWSER_TIME_ZONE: String = {
+        let format = Intl::DateTimeFormat::new(&Array::new(), &Object::new());
+        Reflect::get(&format.resolved_options(), &JsValue::from_str("timeZone"))
+            .ok()
+            .and_then(|z| z.as_string())
+            .unwrap_or_else(|| UTC.into())
+    };
+    static FORMATS: RefCell<HashMap<String, Intl::DateTimeFormat>> = RefCell::default();
+}
+
+pub fn browser_time_zone() -> String {
+    BROWSER_TIME_ZONE.with(Clone::clone)
+}
+
+fn zone_format(zone: &str) -> Intl::DateTimeFormat {
+    FORMATS.with(|formats| {
+        formats
+            .borrow_mut()
+            .entry(zone.into())
+            .or_insert_with(|| {
+                Intl::DateTimeFormat::new(
+                    &Array::of1(&JsValue::from_str("en-US")),
+                    &intl_options(&[
+                        ("timeZone", zone),
+                        ("hourCycle", "h23"),
+                        ("year", "numeric"),
+                        ("month", "numeric"),
+                        ("day", "numeric"),
+                        ("hour", "numeric"),
+                        ("minute", "numeric"),
+                        ("second", "numeric"),
+                    ]),
+                )
+            })
+            .clone()
+    })
+}
+
+pub fn time_zones() -> Vec<String> {
+    let intl = Reflect::get(&web_sys::js_sys::global(), &JsValue::from_str("Intl"));
+    let supported = intl.as_ref().ok().and_then(|intl| {
+        Reflect::get(intl, &JsValue::from_str("supportedValuesOf"))
+            .ok()?
+            .dyn_into::<Function>()
+            .ok()?
+            .call1(intl, &JsValue::from_str("timeZone"))
+            .ok()?
+            .dyn_into::<Array>()
+            .ok()
+    });
+    let mut zones: Vec<String> = supported
+        .map(|a| a.iter().filter_map(|z| z.as_string()).collect())
+        .unwrap_or_default();
+    for zone in [browser_time_zone(), UTC.into()] {
+        if !zones.contains(&zone) {
+            zones.push(zone);
+        }
+    }
+    zones.sort();
+    zones
+}
+
+/// The UTC offset in seconds of `zone` at `timestamp`.
+pub fn offset(zone: &str, timestamp: i64) -> i32 {
+    if zone == UTC {
+        return 0;
+    }
+    let format = zone_format(zone);
+    let date = Date::new(&JsValue::from_f64(timestamp as f64 * 1000.0));
+    let mut fields = [0u32; 6];
+    for part in format.format_to_parts(&date).iter() {
+        let get = |key: &str| {
+            Reflect::get(&part, &JsValue::from_str(key))
+                .ok()
+                .and_then(|v| v.as_string())
+                .unwrap_or_default()
+        };
+        let index = match get("type").as_str() {
+            "year" => 0,
+            "month" => 1,
+            "day" => 2,
+            "hour" => 3,
+            "minute" => 4,
+            "second" => 5,
+            _ => continue,
+        };
+        fields[index] = get("value").parse().unwrap_or_default();
+    }
+    let [year, month, day, hour, minute, second] = fields;
+    NaiveDate::from_ymd_opt(year as i32, month, day)
+        .and_then(|d| d.and_hms_opt(hour % 24, minute, second))
+        .map(|local| (local.and_utc().timestamp() - timestamp) as i32)
+        .unwrap_or_default()
+}
+
+#[component]
+pub fn Expiration(timestamp: i64) -> Element {
+    let local = format_local(timestamp, offset(&browser_time_zone(), timestamp));
+    let utc = format_utc(timestamp);
+    rsx! {
+        span { title: "{utc}", "{local}" }
+    }
+}
+
+#[component]
+pub fn TimeZoneSelect(value: String, onchange: EventHandler<String>) -> Element {
+    let zones = use_hook(time_zones);
+    rsx! {
+        select {
+            class: "border border-gray-300 px-2",
+            name: "timeZone",
+            cursor: "pointer",
+            onchange: move |e| onchange.call(e.value()),
+            for zone in zones {
+                option { selected: zone == value, value: "{zone}", "{zone}" }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2026-03-29 01:00:00 UTC, when Europe/Berlin moves from +01:00 to +02:00.
+    const TRANSITION: i64 = 1_774_746_000;
+
+    fn berlin(timestamp: i64) -> i32 {
+        if timestamp < TRANSITION {
+            3600
+        } else {
+            7200
+        }
+    }
+
+    #[test]
+    fn datetime_local() {
+        let local = parse_datetime_local("2026-10-19T12:30").unwrap();
+        assert_eq!(to_timestamp(local, |_| 0), 1_792_413_000);
+        assert_eq!(
+            parse_datetime_local("2026-10-19T12:30:15"),
+            Some(local + chrono::Duration::seconds(15))
+        );
+        assert_eq!(parse_datetime_local("2026-10-19 12:30"), None);
+        assert_eq!(to_datetime_local(1_792_413_000, 3600), "2026-10-19T13:30");
+    }
+
+    #[test]
+    fn periods() {
+        // Monday 2026-10-19 12:30 UTC.
+        let now = 1_792_413_000;
+        assert_eq!(end_of(Period::Day, now, 0), 1_792_454_400);
+        assert_eq!(end_of(Period::Day, now, 3600), 1_792_450_800);
+        assert_eq!(end_of(Period::Week, now, 0), 1_792_972_800);
+        assert_eq!(end_of(Period::Month, now, 0), 1_793_491_200);
+    }
+
+    #[test]
+    fn around_dst() {
+        let before = parse_datetime_local("2026-03-29T01:30").unwrap();
+        assert_eq!(to_timestamp(before, berlin), TRANSITION - 1800);
+        let after = parse_datetime_local("2026-03-29T04:00").unwrap();
+        assert_eq!(to_timestamp(after, berlin), TRANSITION + 3600);
+    }
+
+    #[test]
+    fn formatting() {
+        assert_eq!(format_offset(19800), "+05:30");
+        assert_eq!(format_offset(-12600), "-03:30");
+        assert_eq!(format_utc(0), "1970-01-01 00:00:00 UTC");
+        assert_eq!(format_local(0, -3600), "1969-12-31 23:00:00 (-01:00)");
+    }
+}
@@ -0,0 +1,235 @@
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::Result,
+    meta::{use_metadata, Metadata},
+    policy::use_policy,
+    store::use_links,
+    time::{self, Expiration},
+    undo,
+    utils::{self, Link},
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Deleted {
+    pub link: Link,
+    pub deleted_at: i64,
+}
+
+pub fn load() -> Vec<Deleted> {
+    utils::load(&utils::backend_storage_key("trash"))
+        .ok()
+        .flatten()
+        .unwrap_or_default()
+}
+
+fn save(trash: &[Deleted]) -> Result<()> {
+    utils::save(&utils::backend_storage_key("trash"), &trash)
+}
+
+fn push(trash: &mut Vec<Deleted>, link: &Link, now: i64) {
+    trash.retain(|d| d.link.short.key != link.short.key);
+    trash.push(Deleted {
+        link: link.clone(),
+        deleted_at: now,
+    });
+}
+
+pub fn add(link: &Link) -> Result<()> {
+    let mut trash = load();
+    push(&mut trash, link, utils::now());
+    save(&trash)
+}
+
+pub fn remove(key: &str) -> Result<()> {
+    let mut trash = load();
+    trash.retain(|d| *d.link.short.key != *key);
+    save(&trash)
+}
+
+/// Drops the tags, notes and stars of links leaving the trash for good,
+/// unless their key is in use again.
+fn drop_metadata(mut metadata: Signal<Metadata>, forgotten: &[Box<str>], existing: &[Box<str>]) {
+    let mut pruned = metadata.peek().clone();
+    let changed = pruned.retain(|key| {
+        !forgotten.iter().any(|k| **k == *key) || existing.iter().any(|k| **k == *key)
+    });
+    if !changed {
+        return;
+    }
+    match pruned.save() {
+        Ok(()) => metadata.set(pruned),
+        Err(e) => utils::alert(&format!("Failed to save the notes.\n\nError: {}", e)),
+    }
+}
+
+#[component]
+pub fn Trash() -> Element {
+    let mut links = use_links();
+    let mut trash = use_signal(load);
+    let mut restoring = use_signal(|| false);
+    let policy = use_policy();
+    let metadata = use_metadata();
+    let zone = time::browser_time_zone();
+    let now = utils::now();
+    let existing: Vec<Box<str>> = match &*links.read() {
+        Some(Ok(links)) => links.iter().map(|l| l.short.key.clone()).collect(),
+        Some(Err(e)) => return Err(e.clone().into()),
+        None => Vec::new(),
+    };
+    // Which keys are in use again is unknown until the links load, and
+    // forgetting drops notes that such a key may still need.
+    let loading = links.read().is_none();
+    let entries: Vec<(Deleted, std::result::Result<(), Box<str>>)> = trash
+        .read()
+        .iter()
+        .rev()
+        .map(|d| {
+            let restorable = if existing.contains(&d.link.short.key) {
+                Err("The key is in use again".into())
+            } else {
+                undo::recreate_body(&d.link, &policy.read(), now).map(|_| ())
+            };
+            (d.clone(), restorable)
+        })
+        .collect();
+    rsx! {
+        div {
+            div { class: "flex flex-col",
+                h1 { class: "text-3xl mx-auto", "Trash" }
+                p { class: "mx-auto text-gray-500", "Links deleted from this browser, kept until restored or forgotten" }
+                p { class: "mx-auto text-sm text-gray-500",
+                    "Their tags, notes and stars are kept until they leave the trash."
+                }
+            }
+            div { class: "flex flex-col w-9/12 sm:w-2/3 mx-auto mt-5",
+                if entries.is_empty() {
+                    p { class: "text-gray-500", "The trash is empty." }
+                } else {
+                    div {
+                        button {
+                            r#type: "button",
+                            class: "border border-gray-300 hover:bg-gray-200 px-2 text-red-500",
+                            cursor: "pointer",
+                            disabled: loading,
+                            onclick: {
+                                let existing = existing.clone();
+                                move |_| {
+                                    if utils::confirm("Forget every deleted link? They cannot be restored afterwards.") {
+                                        let forgotten: Vec<Box<str>> = trash
+                                            .read()
+                                            .iter()
+                                            .map(|d| d.link.short.key.clone())
+                                            .collect();
+                                        match save(&[]) {
+                                            Ok(()) => {
+                                                trash.set(Vec::new());
+                                                drop_metadata(metadata, &forgotten, &existing);
+                                            }
+                                            Err(e) => utils::alert(&format!("Failed to save the trash.\n\nError: {}", e)),
+                                        }
+                                    }
+                                }
+                            },
+                            "Empty trash"
+                        }
+                    }
+                }
+                for (deleted , restorable) in entries {
+                    div { class: "flex flex-wrap gap-1 items-start border-b border-gray-300 py-1",
+                        div { class: "flex-1 break-all",
+                            p { "{deleted.link.short.key}" }
+                            p { class: "text-sm",
+                                "{deleted.link.url.as_deref().unwrap_or(\"(no URL)\")}"
+                            }
+                            p { class: "text-sm text-gray-500",
+                                "Deleted {time::format_local(deleted.deleted_at, time::offset(&zone, deleted.deleted_at))}"
+                                if let Some(timestamp) = deleted.link.expiration {
+                                    ", expires "
+                                    Expiration { timestamp }
+                                }
+                            }
+                            if let Err(e) = &restorable {
+                                p { class: "text-sm text-red-500", "Cannot be restored: {e}" }
+                            }
+                        }
+                        button {
+                            r#type: "button",
+                            class: "border border-gray-300 hover:bg-gray-200 px-1",
+                            cursor: "pointer",
+                            disabled: restoring() || restorable.is_err(),
+                            onclick: {
+                                let link = deleted.link.clone();
+                                move |_| {
+                                    let link = link.clone();
+                                    async move {
+                                        restoring.set(true);
+                                        let policy = policy.read().clone();
+                                        if let Err(e) = undo::restore(&link, &policy).await {
+                                            utils::alert(&e);
+                                        }
+                                        restoring.set(false);
+                                        links.restart();
+                                        trash.set(load());
+                                    }
+                                }
+                            },
+                            "Restore"
+                        }
+                        button {
+                            r#type: "button",
+                            class: "border border-gray-300 hover:bg-gray-200 px-1",
+                            cursor: "pointer",
+                            disabled: loading,
+                            onclick: {
+                                let key = deleted.link.short.key.clone();
+                                let existing = existing.clone();
+                                move |_| {
+                                    match remove(&key) {
+                                        Ok(()) => {
+                                            trash.set(load());
+                                            drop_metadata(metadata, std::slice::from_ref(&key), &existing);
+                                        }
+                                        Err(e) => utils::alert(&format!("Failed to save the trash.\n\nError: {}", e)),
+                                    }
+                                }
+                            },
+                            "Forget"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_792_413_000;
+
+    #[test]
+    fn pushing() {
+        let mut trash = Vec::new();
+        push(
+            &mut trash,
+            &utils::test_link("a", Some("https://a.b"), None),
+            NOW,
+        );
+        push(
+            &mut trash,
+            &utils::test_link("b", Some("https://c.d"), None),
+            NOW,
+        );
+        push(
+            &mut trash,
+            &utils::test_link("a", Some("https://e.f"), None),
+            NOW + 1,
+        );
+        let keys: Vec<&str> = trash.iter().map(|d| d.link.short.key.as_ref()).collect();
+        assert_eq!(keys, ["b", "a"]);
+        assert_eq!(trash[1].link.url.as_deref(), Some("https://e.f"));
+    }
+}
@@ -0,0 +1,279 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use dioxus::prelude::*;
+
+use crate::{
+    form::MIN_EXPIRATION_TTL,
+    history::{self, Removal},
+    policy::{use_policy, Policy},
+    store::use_links,
+    trash,
+    utils::{self, CreateRequestBody, Link, UpdateRequestBody},
+};
+
+/// How long the undo toast stays up.
+const UNDO_SECONDS: i32 = 10;
+
+static NEXT_TOAST: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Undo {
+    /// Create a deleted link again.
+    Recreate(Link),
+    /// Put an updated link back the way it was.
+    Revert { before: Link, after: Link },
+}
+
+impl Undo {
+    /// The undo for sending `body` to update `before`.
+    pub fn revert(before: Link, body: &UpdateRequestBody, now: i64) -> Self {
+        let after = Link {
+            url: Some(body.url.clone()),
+            expiration: body
+                .expiration_ttl
+                .map(|ttl| now + i64::from(ttl))
+                .or(body.expiration),
+            ..before.clone()
+        };
+        Undo::Revert { before, after }
+    }
+
+    pub async fn run(&self, policy: &Policy) -> Result<(), Box<str>> {
+        match self {
+            Undo::Recreate(link) => restore(link, policy).await,
+            Undo::Revert { before, after } => {
+                let body = revert_body(before, after, policy, utils::now())
+                    .map_err(|e| format!("{} cannot be reverted: {}", before.short.key, e))?;
+                history::update_link(after, body).await.map_err(|e| {
+                    format!("Failed to revert {}.\n\nError: {}", before.short.key, e).into()
+                })
+            }
+        }
+    }
+}
+
+fn check_expiration(link: &Link, now: i64) -> Result<(), Box<str>> {
+    match link.expiration {
+        Some(expiration) if expiration < now + i64::from(MIN_EXPIRATION_TTL) => {
+            Err("Its expiration has passed".into())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// The request that creates `link` again under its key, with what is left of
+/// its expiration. The key points nowhere now, so the destination is checked
+/// against the domain policy.
+pub fn recreate_body(
+    link: &Link,
+    policy: &Policy,
+    now: i64,
+) -> Result<CreateRequestBody, Box<str>> {
+    let url = link.url.clone().ok_or("It had no destination URL")?;
+    check_expiration(link, now)?;
+    policy.check_change(None, &url)?;
+    Ok(CreateRequestBody {
+        url,
+        short: Some(link.short.key.clone()),
+        length: None,
+        number: None,
+        capital: None,
+        lowercase: None,
+        expiration: link.expiration,
+        expiration_ttl: None,
+    })
+}
+
+/// The request that reapplies the previous state of an updated link, now in
+/// the state `after`. The domain policy only applies if the destination changes back.
+pub fn revert_body(
+    before: &Link,
+    after: &Link,
+    policy: &Policy,
+    now: i64,
+) -> Result<UpdateRequestBody, Box<str>> {
+    let url = before.url.clone().ok_or("It had no destination URL")?;
+    check_expiration(before, now)?;
+    policy.check_change(after.url.as_deref(), &url)?;
+    Ok(UpdateRequestBody {
+        short: before.short.key.clone(),
+        url,
+        expiration: before.expiration,
+        expiration_ttl: None,
+    })
+}
+
+/// Creates a deleted link again and takes it out of the trash.
+pub async fn restore(link: &Link, policy: &Policy) -> Result<(), Box<str>> {
+    let key = &link.short.key;
+    let body = recreate_body(link, policy, utils::now())
+        .map_err(|e| format!("{} cannot be restored: {}", key, e))?;
+    let short = history::create_link(body)
+        .await
+        .map_err(|e| format!("Failed to restore {}.\n\nError: {}", key, e))?;
+    if short.key != *key {
+        let stray = Link {
+            short,
+            ..link.clone()
+        };
+        let cleanup = match history::delete_link(&stray, Removal::Stray).await {
+            Ok(()) => "It was deleted again.".to_string(),
+            Err(e) => format!("Deleting it again failed: {}", e),
+        };
+        return Err(format!(
+            "Failed to restore {}: the backend created {} instead. {}",
+            key, stray.short.key, cleanup
+        )
+        .into());
+    }
+    trash::remove(key).map_err(|e| {
+        format!(
+            "{} was restored but is still listed in the trash.\n\nError: {}",
+            key, e
+        )
+        .into()
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Toast {
+    id: u64,
+    pub message: String,
+    pub undo: Vec<Undo>,
+}
+
+pub fn use_undo_provider() -> Signal<Option<Toast>> {
+    use_context_provider(|| Signal::new(None))
+}
+
+pub fn use_undo() -> Signal<Option<Toast>> {
+    use_context()
+}
+
+/// Shows `message` with an Undo button for a few seconds.
+pub fn offer(mut toast: Signal<Option<Toast>>, message: String, undo: Vec<Undo>) {
+    let id = NEXT_TOAST.fetch_add(1, Ordering::Relaxed);
+    toast.set(Some(Toast { id, message, undo }));
+    // Not tied to the caller, which may unmount right away, e.g. by navigating.
+    spawn_forever(async move {
+        utils::sleep(UNDO_SECONDS * 1000).await;
+        // A newer toast stays up for its own full time.
+        if toast.peek().as_ref().is_some_and(|t| t.id == id) {
+            toast.set(None);
+        }
+    });
+}
+
+#[component]
+pub fn UndoToast() -> Element {
+    let mut toast = use_undo();
+    let mut links = use_links();
+    let policy = use_policy();
+    let Some(current) = toast() else {
+        return rsx! {};
+    };
+    rsx! {
+        div { class: "fixed bottom-4 right-4 z-10 shadow bg-gray-800 text-white px-2 py-1 flex gap-2 items-center print:hidden",
+            span { "{current.message}" }
+            if !current.undo.is_empty() {
+                button {
+                    r#type: "button",
+                    class: "underline",
+                    cursor: "pointer",
+                    onclick: move |_| {
+                        let undo = current.undo.clone();
+                        async move {
+                            toast.set(None);
+                            let mut failed = Vec::new();
+                            for undo in undo {
+                                let policy = policy.read().clone();
+                                if let Err(e) = undo.run(&policy).await {
+                                    failed.push(e.to_string());
+                                }
+                            }
+                            links.restart();
+                            if !failed.is_empty() {
+                                utils::alert(&failed.join("\n\n"));
+                            }
+                        }
+                    },
+                    "Undo"
+                }
+            }
+            button {
+                r#type: "button",
+                cursor: "pointer",
+                title: "Dismiss",
+                onclick: move |_| toast.set(None),
+                "×"
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_792_413_000;
+
+    #[test]
+    fn recreating() {
+        let link = utils::test_link("a", Some("https://a.b"), Some(NOW + 3600));
+        let none = Policy::default();
+        let body = recreate_body(&link, &none, NOW).unwrap();
+        assert_eq!(body.short.as_deref(), Some("a"));
+        assert_eq!(body.url.as_ref(), "https://a.b");
+        assert_eq!(body.expiration, Some(NOW + 3600));
+        let permanent = utils::test_link("a", Some("https://a.b"), None);
+        assert_eq!(
+            recreate_body(&permanent, &none, NOW).unwrap().expiration,
+            None
+        );
+        assert!(recreate_body(
+            &utils::test_link("a", Some("https://a.b"), Some(NOW)),
+            &none,
+            NOW
+        )
+        .is_err());
+        assert!(recreate_body(&utils::test_link("a", None, None), &none, NOW).is_err());
+        let blocked = Policy {
+            blocked: vec!["a.b".into()],
+            ..Default::default()
+        };
+        assert!(recreate_body(&permanent, &blocked, NOW).is_err());
+    }
+
+    #[test]
+    fn reverting() {
+        let before = utils::test_link("a", Some("https://a.b"), None);
+        let none = Policy::default();
+        let body = revert_body(&before, &before, &none, NOW).unwrap();
+        assert_eq!(body.short.as_ref(), "a");
+        assert_eq!(body.url.as_ref(), "https://a.b");
+        assert_eq!(body.expiration, None);
+        let expired = utils::test_link("a", Some("https://a.b"), Some(NOW + 10));
+        assert!(revert_body(&expired, &before, &none, NOW).is_err());
+        let allowed = Policy {
+            allowed: vec!["c.d".into()],
+            ..Default::default()
+        };
+        // Going back to a.b from c.d breaks the policy, only moving the expiration back does not.
+        let elsewhere = utils::test_link("a", Some("https://c.d"), None);
+        assert!(revert_body(&before, &elsewhere, &allowed, NOW).is_err());
+        let later = utils::test_link("a", Some("https://a.b"), Some(NOW + 3600));
+        assert!(revert_body(&before, &later, &allowed, NOW).is_ok());
+        let update = UpdateRequestBody {
+            short: "a".into(),
+            url: "https://c.d".into(),
+            expiration: None,
+            expiration_ttl: Some(3600),
+        };
+        let Undo::Revert { before: b, after } = Undo::revert(before.clone(), &update, NOW) else {
+            panic!("not a revert");
+        };
+        assert_eq!(b, before);
+        assert_eq!(after.url.as_deref(), Some("https://c.d"));
+        assert_eq!(after.expiration, Some(NOW + 3600));
+    }
+}
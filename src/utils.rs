@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
+use web_sys::wasm_bindgen::JsCast;
+
+use crate::error::{Error, Result};
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Response<T> {
@@ -34,6 +37,7 @@ pub struct Short {
 #[derive(Deserialize, Serialize, Debug)]
 pub struct CreateRequestBody {
     pub url: Box<str>,
+    pub short: Option<Box<str>>,
     pub length: Option<u16>,
     pub number: Option<bool>,
     pub capital: Option<bool>,
@@ -56,40 +60,139 @@ pub struct UpdateRequestBody {
     pub expiration_ttl: Option<u32>,
 }
 
-fn get_login_info() -> Option<(Box<str>, Box<str>)> {
-    let window = web_sys::window().unwrap();
-    let storage = window.local_storage().unwrap().unwrap();
-    let url = match storage.get_item("backendUrl").unwrap() {
+pub fn window() -> Result<web_sys::Window> {
+    web_sys::window().ok_or(Error::NoWindow)
+}
+
+pub fn storage() -> Result<web_sys::Storage> {
+    window()?.local_storage()?.ok_or(Error::NoStorage)
+}
+
+pub fn alert(msg: &str) {
+    if let Ok(window) = window() {
+        let _ = window.alert_with_message(msg);
+    }
+}
+
+pub fn confirm(msg: &str) -> bool {
+    window()
+        .and_then(|w| Ok(w.confirm_with_message(msg)?))
+        .unwrap_or(false)
+}
+
+pub fn reload() {
+    if let Ok(window) = window() {
+        let _ = window.location().reload();
+    }
+}
+
+/// Saves `href` as `name` through a temporary link, for downloads that are
+/// only worth building once they are asked for.
+pub fn download(name: &str, href: &str) -> Result<()> {
+    let document = window()?
+        .document()
+        .ok_or(Error::Js("The document is not available".into()))?;
+    let link = document.create_element("a")?;
+    link.set_attribute("href", href)?;
+    link.set_attribute("download", name)?;
+    link.dyn_into::<web_sys::HtmlElement>()
+        .map_err(|_| Error::Js("Not an HTML element".into()))?
+        .click();
+    Ok(())
+}
+
+/// Downloads `text` as `name`, telling the user if the browser refuses.
+pub fn export(name: &str, mime: &str, text: &str) {
+    let href = format!("data:{};charset=utf-8,{}", mime, crate::query::encode(text));
+    if let Err(e) = download(name, &href) {
+        alert(&format!("Failed to download {}.\n\nError: {}", name, e));
+    }
+}
+
+pub fn load<T: serde::de::DeserializeOwned>(key: &str) -> Result<Option<T>> {
+    match storage()?.get_item(key)? {
+        Some(value) => Ok(Some(serde_json::from_str(&value)?)),
+        None => Ok(None),
+    }
+}
+
+pub fn save<T: Serialize>(key: &str, value: &T) -> Result<()> {
+    storage()?.set_item(key, &serde_json::to_string(value)?)?;
+    Ok(())
+}
+
+pub fn logout() -> Result<()> {
+    let storage = storage()?;
+    storage.remove_item("backendUrl")?;
+    storage.remove_item("token")?;
+    Ok(())
+}
+
+/// The host and port of the backend, without logging out when it is missing.
+pub fn backend_host() -> Option<String> {
+    let url = storage().ok()?.get_item("backendUrl").ok()??;
+    Some(
+        url.trim_start_matches("http://")
+            .trim_start_matches("https://")
+            .trim_end_matches('/')
+            .to_string(),
+    )
+}
+
+/// A localStorage key for data that belongs to the current backend, since
+/// keys and settings only make sense on the backend they came from.
+pub fn backend_storage_key(prefix: &str) -> String {
+    format!("{}:{}", prefix, backend_host().unwrap_or_default())
+}
+
+pub fn now() -> i64 {
+    (web_sys::js_sys::Date::now() / 1000.0) as i64
+}
+
+pub async fn sleep(millis: i32) {
+    let promise = web_sys::js_sys::Promise::new(&mut |resolve, _| {
+        if let Ok(window) = window() {
+            let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(&resolve, millis);
+        }
+    });
+    let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+}
+
+fn logout_with_message(msg: &str) {
+    let _ = logout();
+    alert(msg);
+    reload();
+}
+
+fn get_login_info() -> Result<(Box<str>, Box<str>)> {
+    let storage = storage()?;
+    let url = match storage.get_item("backendUrl")? {
         Some(url) => url.into_boxed_str(),
         None => {
-            storage.clear().unwrap();
-            window
-                .alert_with_message("Failed to fetch the backend URL. Please login again.")
-                .unwrap();
-            window.location().reload().unwrap();
-            return None;
+            logout_with_message("Failed to fetch the backend URL. Please login again.");
+            return Err(Error::NotLoggedIn);
         }
     };
-    let token = match storage.get_item("token").unwrap() {
+    let token = match storage.get_item("token")? {
         Some(token) => token.into_boxed_str(),
         None => {
-            storage.clear().unwrap();
-            window
-                .alert_with_message("Failed to fetch the token. Please login again.")
-                .unwrap();
-            window.location().reload().unwrap();
-            return None;
+            logout_with_message("Failed to fetch the token. Please login again.");
+            return Err(Error::NotLoggedIn);
         }
     };
-    Some((url, token))
+    Ok((url, token))
 }
 
-pub async fn fetch_links() -> Box<[Link]> {
-    let null_link = Box::from([]);
-    let (url, token) = match get_login_info() {
-        Some((url, token)) => (url, token),
-        None => return null_link,
-    };
+async fn error_message<T: serde::de::DeserializeOwned>(response: reqwest::Response) -> Error {
+    let status = response.status();
+    match response.json::<Response<T>>().await {
+        Ok(response) => Error::Backend(response.msg),
+        Err(_) => Error::Backend(format!("The backend responded with {}", status).into()),
+    }
+}
+
+pub async fn fetch_links() -> Result<Box<[Link]>> {
+    let (url, token) = get_login_info()?;
     let mut links: Vec<Link> = Vec::new();
     let mut cursor: Option<Box<str>> = None;
     loop {
@@ -101,161 +204,118 @@ pub async fn fetch_links() -> Box<[Link]> {
         } else {
             req
         };
-        match req.send().await {
-            Ok(x) => {
-                let list_data = x.json::<Response<ListData>>().await.unwrap().data.unwrap();
-                links.extend_from_slice(&list_data.links);
-                cursor = list_data.cursor;
-                if list_data.list_complete {
-                    break;
-                }
-            }
-            Err(e) => {
-                web_sys::window()
-                    .unwrap()
-                    .alert_with_message(
-                        format!("Failed to fetch the list. Please try again later.\n{}", e)
-                            .as_str(),
-                    )
-                    .unwrap();
-                return null_link;
-            }
+        let response = req.send().await?;
+        if !response.status().is_success() {
+            return Err(error_message::<ListData>(response).await);
+        }
+        let list_data = response
+            .json::<Response<ListData>>()
+            .await?
+            .data
+            .ok_or(Error::MissingData)?;
+        links.extend_from_slice(&list_data.links);
+        cursor = list_data.cursor;
+        if list_data.list_complete {
+            break;
         }
     }
-    links.into_boxed_slice()
+    Ok(links.into_boxed_slice())
 }
 
-pub async fn update_link(body: UpdateRequestBody) -> bool {
-    let (url, token) = match get_login_info() {
-        Some((url, token)) => (url, token),
-        None => return false,
-    };
-    match reqwest::Client::new()
+pub async fn update_link(body: UpdateRequestBody) -> Result<()> {
+    let (url, token) = get_login_info()?;
+    let response = reqwest::Client::new()
         .put(format!("{}/api/v1/update", url))
         .bearer_auth(&token)
         .json(&body)
         .send()
-        .await
-    {
-        Ok(response) => {
-            if !response.status().is_success() {
-                web_sys::window()
-                    .unwrap()
-                    .alert_with_message(&format!(
-                        "Failed to update the link.\n\nError: {}",
-                        response.json::<Response<()>>().await.unwrap().msg
-                    ))
-                    .unwrap();
-                return false;
-            }
-            true
-        }
-        Err(e) => {
-            web_sys::window()
-                .unwrap()
-                .alert_with_message(
-                    format!(
-                        "Failed to update the link. Please try again later.\n\nError: {}",
-                        e
-                    )
-                    .as_str(),
-                )
-                .unwrap();
-            false
-        }
+        .await?;
+    if !response.status().is_success() {
+        return Err(error_message::<()>(response).await);
     }
+    Ok(())
 }
 
-pub async fn delete_link(short: &str) -> bool {
-    let (url, token) = match get_login_info() {
-        Some((url, token)) => (url, token),
-        None => return false,
-    };
-    match reqwest::Client::new()
+pub async fn delete_link(short: &str) -> Result<()> {
+    let (url, token) = get_login_info()?;
+    let response = reqwest::Client::new()
         .delete(format!("{}/api/v1/delete", url))
         .body(format!(r#"{{"short": "{}"}}"#, short))
         .bearer_auth(&token)
         .send()
-        .await
-    {
-        Ok(response) => {
-            if !response.status().is_success() {
-                web_sys::window()
-                    .unwrap()
-                    .alert_with_message(&format!(
-                        "Failed to delete the link.\n\nError: {}",
-                        response.json::<Response<()>>().await.unwrap().msg
-                    ))
-                    .unwrap();
-                return false;
-            }
-            true
-        }
-        Err(e) => {
-            web_sys::window()
-                .unwrap()
-                .alert_with_message(
-                    format!(
-                        "Failed to delete the link. Please try again later.\n\nError: {}",
-                        e
-                    )
-                    .as_str(),
-                )
-                .unwrap();
-            false
-        }
+        .await?;
+    if !response.status().is_success() {
+        return Err(error_message::<()>(response).await);
     }
+    Ok(())
 }
 
-pub async fn create_link(req: CreateRequestBody) -> Box<str> {
-    let null_response = Box::from("");
-    let (url, token) = match get_login_info() {
-        Some((url, token)) => (url, token),
-        None => return null_response,
-    };
-    match reqwest::Client::new()
+pub async fn create_link(req: CreateRequestBody) -> Result<Short> {
+    let (url, token) = get_login_info()?;
+    let response = reqwest::Client::new()
         .post(format!("{}/api/v1/create", url))
         .bearer_auth(&token)
         .json(&req)
         .send()
-        .await
-    {
-        Ok(response) => {
-            if response.status().is_success() {
-                let response: Response<CreateData> = response.json().await.unwrap();
-                response
-                    .data
-                    .unwrap()
-                    .short
-                    .trim_start_matches(&format!(
-                        "{}/",
-                        url.trim_start_matches("http://")
-                            .trim_start_matches("https://"),
-                    ))
-                    .into()
-            } else {
-                web_sys::window()
-                    .unwrap()
-                    .alert_with_message(&format!(
-                        "Failed to create the link.\n\nError: {}",
-                        response.json::<Response<CreateData>>().await.unwrap().msg
-                    ))
-                    .unwrap();
-                null_response
-            }
-        }
-        Err(e) => {
-            web_sys::window()
-                .unwrap()
-                .alert_with_message(
-                    format!(
-                        "Failed to create the link. Please try again later.\n\nError: {}",
-                        e
-                    )
-                    .as_str(),
-                )
-                .unwrap();
-            null_response
-        }
+        .await?;
+    if !response.status().is_success() {
+        return Err(error_message::<CreateData>(response).await);
+    }
+    let response: Response<CreateData> = response.json().await?;
+    Ok(created_short(
+        &url,
+        &response.data.ok_or(Error::MissingData)?.short,
+    ))
+}
+
+// The create response only carries `host/key`, the other forms are rebuilt
+// from the backend URL.
+fn created_short(backend_url: &str, short: &str) -> Short {
+    let host = backend_url
+        .trim_start_matches("http://")
+        .trim_start_matches("https://")
+        .trim_end_matches('/');
+    let scheme = if backend_url.starts_with("http://") {
+        "http://"
+    } else {
+        "https://"
+    };
+    let key = short.trim_start_matches(&format!("{}/", host));
+    let no_https = format!("{}/{}", host, key);
+    Short {
+        key: key.into(),
+        full: format!("{}{}", scheme, no_https).into(),
+        no_https: no_https.into(),
+    }
+}
+
+#[cfg(test)]
+pub fn test_link(key: &str, url: Option<&str>, expiration: Option<i64>) -> Link {
+    Link {
+        short: Short {
+            key: key.into(),
+            no_https: format!("s.example/{}", key).into(),
+            full: format!("https://s.example/{}", key).into(),
+        },
+        url: url.map(Box::from),
+        expiration,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn created() {
+        let short = created_short("https://s.example", "s.example/abc");
+        assert_eq!(short, test_link("abc", None, None).short);
+        assert_eq!(created_short("https://s.example/", "abc"), short);
+        assert_eq!(
+            created_short("http://localhost:8787", "localhost:8787/abc")
+                .full
+                .as_ref(),
+            "http://localhost:8787/abc"
+        );
     }
 }
@@ -0,0 +1,246 @@
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{error::Result, list::Filter, share::CopyButton, utils};
+
+/// A named List filter with its sort and columns.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedView {
+    pub name: Box<str>,
+    pub filter: Filter,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Views {
+    pub views: Vec<SavedView>,
+}
+
+impl Views {
+    pub fn load() -> Self {
+        utils::load(&utils::backend_storage_key("views"))
+            .ok()
+            .flatten()
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        utils::save(&utils::backend_storage_key("views"), self)
+    }
+
+    /// Adds `view`, replacing a view with the same name.
+    pub fn save_view(&mut self, view: SavedView) {
+        match self.views.iter_mut().find(|v| v.name == view.name) {
+            Some(existing) => *existing = view,
+            None => self.views.push(view),
+        }
+    }
+
+    pub fn export(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /// Adds the views of an export, replacing those with the same names.
+    /// Returns the number of imported views.
+    pub fn import(&mut self, json: &str) -> std::result::Result<usize, Box<str>> {
+        let imported: Views =
+            serde_json::from_str(json).map_err(|e| format!("Not a views export: {}", e))?;
+        let count = imported.views.len();
+        for view in imported.views {
+            self.save_view(view);
+        }
+        Ok(count)
+    }
+}
+
+pub fn use_views_provider() -> Signal<Views> {
+    use_context_provider(|| Signal::new(Views::load()))
+}
+
+pub fn use_views() -> Signal<Views> {
+    use_context()
+}
+
+fn save(mut views: Signal<Views>, new: Views) {
+    match new.save() {
+        Ok(()) => views.set(new),
+        Err(e) => utils::alert(&format!("Failed to save the views.\n\nError: {}", e)),
+    }
+}
+
+#[component]
+pub fn SaveView(filter: Filter) -> Element {
+    let views = use_views();
+    let mut name = use_signal(String::new);
+    // The router keeps the List URL in sync with the filter.
+    let url = utils::window()
+        .ok()
+        .and_then(|w| w.location().href().ok())
+        .unwrap_or_default();
+    rsx! {
+        div { class: "flex gap-1 items-center",
+            input {
+                class: "border border-gray-300 px-2 w-40",
+                r#type: "text",
+                placeholder: "View name",
+                value: "{name}",
+                oninput: move |e| name.set(e.value()),
+            }
+            button {
+                r#type: "button",
+                class: "border border-gray-300 hover:bg-gray-200 px-2",
+                cursor: "pointer",
+                disabled: name.read().trim().is_empty(),
+                onclick: move |_| {
+                    let view = SavedView {
+                        name: name.read().trim().into(),
+                        filter: filter.clone(),
+                    };
+                    let mut new = views.read().clone();
+                    new.save_view(view);
+                    save(views, new);
+                    name.set(String::new());
+                },
+                "Save view"
+            }
+            CopyButton { text: url, label: "Copy link".to_string() }
+        }
+    }
+}
+
+/// The saved views as SideBar entries below the List.
+#[component]
+pub fn ViewLinks() -> Element {
+    let views = use_views();
+    rsx! {
+        for view in views.read().views.iter() {
+            li { class: "flex items-stretch w-20 hover:bg-gray-200 border-b border-gray-300",
+                Link {
+                    class: "pl-6 pr-1 text-xs truncate",
+                    to: view.filter.route(),
+                    title: "{view.name}",
+                    "{view.name}"
+                }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn ViewsPanel() -> Element {
+    let views = use_views();
+    let backend = utils::backend_host().unwrap_or_default();
+    rsx! {
+        if views.read().views.is_empty() {
+            p { class: "text-gray-500", "None yet, save one from the List." }
+        }
+        for (i , view) in views.read().views.iter().enumerate() {
+            div { class: "flex gap-1 items-start",
+                Link { class: "flex-1 break-all underline", to: view.filter.route(), "{view.name}" }
+                button {
+                    r#type: "button",
+                    class: "border border-gray-300 hover:bg-gray-200 px-1",
+                    cursor: "pointer",
+                    onclick: move |_| {
+                        let mut new = views.read().clone();
+                        new.views.remove(i);
+                        save(views, new);
+                    },
+                    "Remove"
+                }
+            }
+        }
+        div { class: "flex flex-wrap gap-1 items-center mt-1",
+            button {
+                r#type: "button",
+                class: "border border-gray-300 hover:bg-gray-200 px-2",
+                cursor: "pointer",
+                onclick: move |_| {
+                    utils::export(
+                        &format!("linkrusk-views-{}.json", backend),
+                        "application/json",
+                        &views.read().export(),
+                    )
+                },
+                "Export"
+            }
+            label { class: "border border-gray-300 hover:bg-gray-200 px-2", cursor: "pointer",
+                "Import"
+                input {
+                    class: "hidden",
+                    r#type: "file",
+                    accept: "application/json,.json",
+                    onchange: move |e| async move {
+                        let Some(engine) = e.files() else {
+                            return;
+                        };
+                        for file in engine.files() {
+                            let Some(json) = engine.read_file_to_string(&file).await else {
+                                continue;
+                            };
+                            let mut new = views.read().clone();
+                            match new.import(&json) {
+                                Ok(count) => {
+                                    save(views, new);
+                                    utils::alert(&format!("Imported {} views", count));
+                                }
+                                Err(e) => utils::alert(&format!("Failed to import {}.\n\nError: {}", file, e)),
+                            }
+                        }
+                    },
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn view(name: &str, tag: &str) -> SavedView {
+        SavedView {
+            name: name.into(),
+            filter: Filter {
+                tag: tag.into(),
+                sort: "domain".into(),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn saving() {
+        let mut views = Views::default();
+        views.save_view(view("marketing", "promo"));
+        views.save_view(view("ops", "infra"));
+        views.save_view(view("marketing", "ads"));
+        assert_eq!(
+            views.views,
+            [view("marketing", "ads"), view("ops", "infra")]
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut views = Views::default();
+        views.save_view(view("marketing", "promo"));
+        let mut other = Views::default();
+        other.save_view(view("marketing", "old"));
+        other.save_view(view("ops", "infra"));
+        assert_eq!(other.import(&views.export()), Ok(1));
+        assert_eq!(
+            other.views,
+            [view("marketing", "promo"), view("ops", "infra")]
+        );
+        assert!(other.import("{}").is_err());
+    }
+
+    #[test]
+    fn missing_filter_fields() {
+        // `#[serde(default)]` on `Filter` fills in the fields a view leaves out.
+        let json = r#"{"views":[{"name":"a","filter":{"tag":"x"}}]}"#;
+        let views: Views = serde_json::from_str(json).unwrap();
+        assert_eq!(views.views[0].filter.tag, "x");
+        assert_eq!(views.views[0].filter.sort, "");
+    }
+}